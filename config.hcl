@@ -0,0 +1,22 @@
+# HCL configuration
+name    = "config.hcl"
+version = 4
+debug   = true
+
+upstream "api" {
+  host = "10.0.0.10"
+  port = 8080
+}
+
+upstream "web" {
+  host = "10.0.0.20"
+  port = 80
+}
+
+listener {
+  port = 443
+}
+
+listener {
+  port = 8443
+}
@@ -0,0 +1,167 @@
+//! [`miette::Diagnostic`] for [`Error`], enabled with the `diagnostics` feature.
+//!
+//! The snippet of an error's [`Location`] is the source code, so reports show the file name
+//! and the real line number with a label under the offending value.
+
+use crate::{Error, Location};
+use miette::{
+    Diagnostic, LabeledSpan, MietteError, MietteSpanContents, SourceCode, SourceSpan, SpanContents,
+};
+use std::fmt::Display;
+
+impl Diagnostic for Error {
+    fn code<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        let code = match self {
+            Error::LoadFileError { .. } => "better_config::load_file",
+            Error::ParseError { .. } => "better_config::parse",
+            Error::ValueError { .. } => "better_config::value",
+            Error::InvalidPathError { .. } => "better_config::invalid_path",
+            Error::ValidationError { .. } => "better_config::validation",
+            Error::UnknownKeysError { .. } => "better_config::unknown_keys",
+            Error::IoError { .. } => "better_config::io",
+        };
+        Some(Box::new(code))
+    }
+
+    fn help<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        let help = match self {
+            Error::LoadFileError { .. } => {
+                "check the path, or end it with `?` to make the file optional".to_string()
+            }
+            Error::ParseError { format, source, .. } => {
+                let unsupported = source
+                    .as_deref()
+                    .and_then(|e| e.downcast_ref::<std::io::Error>())
+                    .is_some_and(|e| e.kind() == std::io::ErrorKind::Unsupported);
+                if unsupported {
+                    format!(
+                        "enable the `{}` feature of better-config",
+                        format.to_lowercase()
+                    )
+                } else if format == "unknown" {
+                    "give the file a known extension such as `.toml`, `.json` or `.yml`".to_string()
+                } else {
+                    return None;
+                }
+            }
+            // a type such as `u16`, or a constraint such as `one of 'a', 'b'`
+            Error::ValueError {
+                key, expected_type, ..
+            } if expected_type.contains(' ') => format!("set `{}` to {}", key, expected_type),
+            Error::ValueError {
+                key, expected_type, ..
+            } => format!("set `{}` to a valid {}", key, expected_type),
+            Error::UnknownKeysError { keys } => {
+                let suggestions: Vec<_> = keys
+                    .iter()
+                    .filter_map(|k| k.suggestion.as_ref())
+                    .map(|s| format!("`{}`", s))
+                    .collect();
+                if suggestions.is_empty() {
+                    "remove the keys, or declare fields that read them".to_string()
+                } else {
+                    format!("did you mean {}?", suggestions.join(", "))
+                }
+            }
+            _ => return None,
+        };
+        Some(Box::new(help))
+    }
+
+    fn source_code(&self) -> Option<&dyn SourceCode> {
+        let location = self.location()?;
+        location.snippet.as_ref()?;
+        Some(location)
+    }
+
+    fn labels(&self) -> Option<Box<dyn Iterator<Item = LabeledSpan> + '_>> {
+        let location = self.location()?;
+        let snippet = location.snippet.as_deref()?;
+        let label = match self {
+            Error::ParseError { format, .. } => format!("invalid {}", format),
+            Error::ValueError { expected_type, .. } => format!("expected {}", expected_type),
+            Error::UnknownKeysError { keys } => format!("unknown key `{}`", keys.first()?.key),
+            _ => return None,
+        };
+
+        // the token starting at the column, e.g. `"abc"` in `port = "abc"`
+        let start = snippet
+            .char_indices()
+            .nth(location.column - 1)
+            .map_or(snippet.len(), |(i, _)| i);
+        let len = snippet[start..]
+            .find(char::is_whitespace)
+            .unwrap_or(snippet.len() - start);
+        let span = LabeledSpan::new_primary_with_span(Some(label), (start, len));
+        Some(Box::new(std::iter::once(span)))
+    }
+}
+
+impl SourceCode for Location {
+    fn read_span<'a>(
+        &'a self,
+        span: &SourceSpan,
+        _context_lines_before: usize,
+        _context_lines_after: usize,
+    ) -> Result<Box<dyn SpanContents<'a> + 'a>, MietteError> {
+        let snippet = self.snippet.as_deref().ok_or(MietteError::OutOfBounds)?;
+        if span.offset() + span.len() > snippet.len() {
+            return Err(MietteError::OutOfBounds);
+        }
+
+        Ok(Box::new(MietteSpanContents::new_named(
+            self.name.clone(),
+            snippet.as_bytes(),
+            (0, snippet.len()).into(),
+            self.line - 1,
+            0,
+            1,
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_value_error_report() {
+        let content = "[database]\nhost = \"db\"\nport = \"abc\"\n";
+        let error = Error::value_conversion_error("database.port", "u16", "\"abc\"")
+            .with_location(Location::in_content("config.toml", content, 3, 8));
+
+        assert_eq!(error.code().unwrap().to_string(), "better_config::value");
+        assert_eq!(
+            error.help().unwrap().to_string(),
+            "set `database.port` to a valid u16"
+        );
+
+        let labels: Vec<_> = error.labels().unwrap().collect();
+        assert_eq!(labels[0].offset(), 7);
+        assert_eq!(labels[0].len(), 5);
+        assert_eq!(labels[0].label(), Some("expected u16"));
+
+        let contents = error
+            .source_code()
+            .unwrap()
+            .read_span(labels[0].inner(), 1, 1)
+            .unwrap();
+        assert_eq!(contents.name(), Some("config.toml"));
+        assert_eq!(contents.line(), 2);
+        assert_eq!(contents.data(), b"port = \"abc\"");
+    }
+
+    #[test]
+    fn test_without_location() {
+        let error = Error::value_conversion_error("PORT", "u16", "abc");
+        assert!(error.source_code().is_none());
+        assert!(error.labels().is_none());
+
+        let error = Error::unsupported_format("config.kdl", "KDL");
+        assert_eq!(
+            error.help().unwrap().to_string(),
+            "enable the `kdl` feature of better-config"
+        );
+        assert!(Error::invalid_path("a", "b").help().is_none());
+    }
+}
@@ -1,6 +1,10 @@
+use crate::utils::keys::UnknownKey;
+use crate::Location;
 use std::{error::Error as StdError, fmt};
 
+/// The errors of loading a configuration, new variants may be added in minor releases.
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum Error {
     /// Failed to load or read a configuration file
     LoadFileError {
@@ -12,17 +16,23 @@ pub enum Error {
         name: String,
         format: String,
         source: Option<Box<dyn StdError + Send + Sync>>,
+        /// Where parsing failed, if the format reports it
+        location: Option<Box<Location>>,
     },
     /// Invalid configuration value or type conversion error
     ValueError {
         key: String,
         expected_type: String,
         actual_value: String,
+        /// Where the value was loaded from, if known
+        location: Option<Box<Location>>,
     },
     /// Invalid file path or configuration target
     InvalidPathError { path: String, reason: String },
     /// Configuration validation failed
     ValidationError { message: String },
+    /// Keys in the configuration that no field reads, see `deny_unknown_keys`
+    UnknownKeysError { keys: Vec<UnknownKey> },
     /// I/O operation failed
     IoError {
         operation: String,
@@ -30,6 +40,8 @@ pub enum Error {
     },
 }
 
+/// The alternate form, `{:#}`, prints the offending line of parse and value errors under the
+/// message.
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
@@ -44,23 +56,38 @@ impl fmt::Display for Error {
                 name,
                 format,
                 source,
+                location,
             } => {
+                if let Some(location) = location {
+                    write!(f, "{}: ", location)?;
+                }
                 write!(f, "Failed to parse {} file {} as {}", format, name, format)?;
                 if let Some(source) = source {
                     write!(f, ": {}", source)?;
                 }
-                Ok(())
+                match location {
+                    Some(location) if f.alternate() => location.render_snippet(f),
+                    _ => Ok(()),
+                }
             }
             Error::ValueError {
                 key,
                 expected_type,
                 actual_value,
+                location,
             } => {
+                if let Some(location) = location {
+                    write!(f, "{}: ", location)?;
+                }
                 write!(
                     f,
                     "Invalid value for key '{}': expected {}, got '{}'",
                     key, expected_type, actual_value
-                )
+                )?;
+                match location {
+                    Some(location) if f.alternate() => location.render_snippet(f),
+                    _ => Ok(()),
+                }
             }
             Error::InvalidPathError { path, reason } => {
                 write!(f, "Invalid path '{}': {}", path, reason)
@@ -68,6 +95,16 @@ impl fmt::Display for Error {
             Error::ValidationError { message } => {
                 write!(f, "Configuration validation failed: {}", message)
             }
+            Error::UnknownKeysError { keys } => {
+                write!(f, "Unknown configuration keys: ")?;
+                for (i, key) in keys.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, "; ")?;
+                    }
+                    write!(f, "{}", key)?;
+                }
+                Ok(())
+            }
             Error::IoError { operation, source } => {
                 write!(f, "I/O operation '{}' failed", operation)?;
                 if let Some(source) = source {
@@ -108,11 +145,22 @@ impl Error {
         }
     }
 
+    pub fn no_files_matched(pattern: &str) -> Self {
+        Error::LoadFileError {
+            name: pattern.to_string(),
+            source: Some(Box::new(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("No files matched '{}'", pattern),
+            ))),
+        }
+    }
+
     pub fn parse_json_error(path: &str, source: impl StdError + Send + Sync + 'static) -> Self {
         Error::ParseError {
             name: path.to_string(),
             format: "JSON".to_string(),
             source: Some(Box::new(source)),
+            location: None,
         }
     }
 
@@ -121,6 +169,7 @@ impl Error {
             name: path.to_string(),
             format: "YAML".to_string(),
             source: Some(Box::new(source)),
+            location: None,
         }
     }
 
@@ -129,6 +178,94 @@ impl Error {
             name: path.to_string(),
             format: "TOML".to_string(),
             source: Some(Box::new(source)),
+            location: None,
+        }
+    }
+
+    pub fn parse_json5_error(path: &str, source: impl StdError + Send + Sync + 'static) -> Self {
+        Error::ParseError {
+            name: path.to_string(),
+            format: "JSON5".to_string(),
+            source: Some(Box::new(source)),
+            location: None,
+        }
+    }
+
+    pub fn parse_ron_error(path: &str, source: impl StdError + Send + Sync + 'static) -> Self {
+        Error::ParseError {
+            name: path.to_string(),
+            format: "RON".to_string(),
+            source: Some(Box::new(source)),
+            location: None,
+        }
+    }
+
+    pub fn parse_hcl_error(path: &str, source: impl StdError + Send + Sync + 'static) -> Self {
+        Error::ParseError {
+            name: path.to_string(),
+            format: "HCL".to_string(),
+            source: Some(Box::new(source)),
+            location: None,
+        }
+    }
+
+    pub fn parse_properties_error(
+        path: &str,
+        source: impl StdError + Send + Sync + 'static,
+    ) -> Self {
+        Error::ParseError {
+            name: path.to_string(),
+            format: "PROPERTIES".to_string(),
+            source: Some(Box::new(source)),
+            location: None,
+        }
+    }
+
+    pub fn parse_xml_error(path: &str, source: impl StdError + Send + Sync + 'static) -> Self {
+        Error::ParseError {
+            name: path.to_string(),
+            format: "XML".to_string(),
+            source: Some(Box::new(source)),
+            location: None,
+        }
+    }
+
+    pub fn parse_kdl_error(path: &str, source: impl StdError + Send + Sync + 'static) -> Self {
+        Error::ParseError {
+            name: path.to_string(),
+            format: "KDL".to_string(),
+            source: Some(Box::new(source)),
+            location: None,
+        }
+    }
+
+    pub fn parse_ini_error(path: &str, source: impl StdError + Send + Sync + 'static) -> Self {
+        Error::ParseError {
+            name: path.to_string(),
+            format: "INI".to_string(),
+            source: Some(Box::new(source)),
+            location: None,
+        }
+    }
+
+    pub fn parse_env_error(path: &str, source: impl StdError + Send + Sync + 'static) -> Self {
+        Error::ParseError {
+            name: path.to_string(),
+            format: "ENV".to_string(),
+            source: Some(Box::new(source)),
+            location: None,
+        }
+    }
+
+    pub fn unsupported_format(path: &str, format: &str) -> Self {
+        Error::ParseError {
+            name: path.to_string(),
+            format: format.to_string(),
+            source: Some(Box::new(std::io::Error::new(
+                std::io::ErrorKind::Unsupported,
+                format!("{} support is not enabled", format),
+            ))),
+            location: None,
         }
     }
 
@@ -144,6 +281,28 @@ impl Error {
             key: key.to_string(),
             expected_type: expected.to_string(),
             actual_value: actual.to_string(),
+            location: None,
+        }
+    }
+
+    /// Attach where a parse or value error happened, other errors and `None` leave it unchanged.
+    pub fn with_location(mut self, at: impl Into<Option<Location>>) -> Self {
+        if let (Error::ParseError { location, .. } | Error::ValueError { location, .. }, Some(at)) =
+            (&mut self, at.into())
+        {
+            *location = Some(Box::new(at));
+        }
+        self
+    }
+
+    /// Where a parse or value error happened, or the first unknown key is defined, if known.
+    pub fn location(&self) -> Option<&Location> {
+        match self {
+            Error::ParseError { location, .. } | Error::ValueError { location, .. } => {
+                location.as_deref()
+            }
+            Error::UnknownKeysError { keys } => keys.first()?.location.as_ref(),
+            _ => None,
         }
     }
 }
@@ -0,0 +1,123 @@
+use std::fmt;
+use std::path::Path;
+
+/// Configuration formats understood by the built-in loaders.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Format {
+    /// TOML, `.toml`
+    Toml,
+    /// JSON, `.json`
+    Json,
+    /// JSON5 or JSON with comments, `.json5` or `.jsonc`
+    Json5,
+    /// YAML, `.yml` or `.yaml`
+    Yml,
+    /// INI, `.ini`
+    Ini,
+    /// dotenv, `.env` or `.env.*`
+    Env,
+    /// KDL, `.kdl`
+    Kdl,
+    /// XML, `.xml`
+    Xml,
+    /// Java properties, `.properties`
+    Properties,
+    /// HCL, `.hcl`
+    Hcl,
+    /// RON, `.ron`
+    Ron,
+}
+
+impl Format {
+    /// Detect the format from a file extension such as `toml` or `yaml`.
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension.to_ascii_lowercase().as_str() {
+            "toml" => Some(Format::Toml),
+            "json" => Some(Format::Json),
+            "json5" | "jsonc" => Some(Format::Json5),
+            "yml" | "yaml" => Some(Format::Yml),
+            "ini" => Some(Format::Ini),
+            "env" => Some(Format::Env),
+            "kdl" => Some(Format::Kdl),
+            "xml" => Some(Format::Xml),
+            "properties" => Some(Format::Properties),
+            "hcl" => Some(Format::Hcl),
+            "ron" => Some(Format::Ron),
+            _ => None,
+        }
+    }
+
+    /// Detect the format from a file path, `.env` and `.env.<stage>` files are dotenv files.
+    pub fn from_path(path: &str) -> Option<Self> {
+        let path = Path::new(path);
+        let file_name = path.file_name()?.to_str()?;
+        if file_name == ".env" || file_name.starts_with(".env.") {
+            return Some(Format::Env);
+        }
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .and_then(Self::from_extension)
+    }
+
+    /// Human readable name used in error messages, e.g. `TOML`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Format::Toml => "TOML",
+            Format::Json => "JSON",
+            Format::Json5 => "JSON5",
+            Format::Yml => "YAML",
+            Format::Ini => "INI",
+            Format::Env => "ENV",
+            Format::Kdl => "KDL",
+            Format::Xml => "XML",
+            Format::Properties => "PROPERTIES",
+            Format::Hcl => "HCL",
+            Format::Ron => "RON",
+        }
+    }
+
+    /// File extensions of this format, without the leading dot.
+    pub fn extensions(&self) -> &'static [&'static str] {
+        match self {
+            Format::Toml => &["toml"],
+            Format::Json => &["json"],
+            Format::Json5 => &["json5", "jsonc"],
+            Format::Yml => &["yml", "yaml"],
+            Format::Ini => &["ini"],
+            Format::Env => &["env"],
+            Format::Kdl => &["kdl"],
+            Format::Xml => &["xml"],
+            Format::Properties => &["properties"],
+            Format::Hcl => &["hcl"],
+            Format::Ron => &["ron"],
+        }
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_extension() {
+        assert_eq!(Format::from_extension("toml"), Some(Format::Toml));
+        assert_eq!(Format::from_extension("YAML"), Some(Format::Yml));
+        assert_eq!(Format::from_extension("jsonc"), Some(Format::Json5));
+        assert_eq!(Format::from_extension("txt"), None);
+    }
+
+    #[test]
+    fn test_from_path() {
+        assert_eq!(Format::from_path("conf.d/10-db.toml"), Some(Format::Toml));
+        assert_eq!(Format::from_path("config.yaml"), Some(Format::Yml));
+        assert_eq!(Format::from_path(".env"), Some(Format::Env));
+        assert_eq!(Format::from_path("deploy/.env.prod"), Some(Format::Env));
+        assert_eq!(Format::from_path("config"), None);
+    }
+}
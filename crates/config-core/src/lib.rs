@@ -1,8 +1,20 @@
+#[cfg(feature = "diagnostics")]
+mod diagnostic;
 mod error;
+mod format;
+mod loaded;
+mod location;
 mod traits;
 pub mod utils;
 
 pub use error::Error;
+pub use format::Format;
+pub use loaded::Loaded;
+pub use location::Location;
 pub use traits::AbstractConfig;
-pub use utils::override_env::{merge_with_env, merge_with_env_uppercase};
+pub use utils::override_env::{merge_loaded_with_env, merge_with_env, merge_with_env_uppercase};
 pub use utils::*;
+
+// the generated `json_schema()` returns a `serde_json::Value`
+#[cfg(feature = "schema")]
+pub use serde_json;
@@ -0,0 +1,112 @@
+use crate::location::Location;
+use std::collections::{HashMap, HashSet};
+
+/// Configuration as a loader returns it, the flattened values with where they are defined and
+/// the keys set to null.
+///
+/// A key set to null, e.g. `port: null` in YAML, is left out of `values` so that it counts as
+/// absent, and kept in `nulls` so that it is told apart from a key that is not set at all.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Loaded {
+    /// The values by flattened key, e.g. `database.port`.
+    pub values: HashMap<String, String>,
+    /// Where each value is defined, for the formats that report positions.
+    pub locations: HashMap<String, Location>,
+    /// The keys set to null, never in `values`.
+    pub nulls: HashSet<String>,
+}
+
+impl Loaded {
+    pub fn new() -> Self {
+        Loaded::default()
+    }
+
+    /// Set `key` to `value`, replacing an earlier value or null and its location.
+    pub fn insert(&mut self, key: String, value: String) {
+        self.nulls.remove(&key);
+        self.locations.remove(&key);
+        self.values.insert(key, value);
+    }
+
+    /// Set `key` to `value` defined at `location`.
+    pub fn insert_located(&mut self, key: String, value: String, location: Location) {
+        self.insert(key.clone(), value);
+        self.locations.insert(key, location);
+    }
+
+    /// Set `key` to null, dropping an earlier value.
+    pub fn insert_null(&mut self, key: String) {
+        self.values.remove(&key);
+        self.locations.remove(&key);
+        self.nulls.insert(key);
+    }
+
+    /// Merge `layer`, loaded after `self`, like a later file of the same target: its values
+    /// replace those of `self` and its null keys drop them.
+    pub fn merge(&mut self, layer: Loaded) {
+        for key in layer.nulls {
+            self.insert_null(key);
+        }
+        for (key, value) in layer.values {
+            self.insert(key, value);
+        }
+        self.locations.extend(layer.locations);
+    }
+}
+
+impl From<HashMap<String, String>> for Loaded {
+    fn from(values: HashMap<String, String>) -> Self {
+        Loaded {
+            values,
+            ..Loaded::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_layers() {
+        let mut loaded = Loaded::new();
+        loaded.insert_located(
+            "host".to_string(),
+            "localhost".to_string(),
+            Location::new("a.yml", 1, 7),
+        );
+        loaded.insert_located(
+            "port".to_string(),
+            "80".to_string(),
+            Location::new("a.yml", 2, 7),
+        );
+        loaded.insert_null("port".to_string());
+        loaded.insert_null("user".to_string());
+        assert!(!loaded.values.contains_key("port"));
+
+        // a later layer sets `user` again and unsets `host`
+        let mut layer = Loaded::new();
+        layer.insert_located(
+            "user".to_string(),
+            "admin".to_string(),
+            Location::new("b.yml", 1, 7),
+        );
+        layer.insert_null("host".to_string());
+        loaded.merge(layer);
+
+        assert_eq!(
+            loaded.values,
+            HashMap::from([("user".to_string(), "admin".to_string())])
+        );
+        assert_eq!(
+            loaded.nulls,
+            HashSet::from(["port".to_string(), "host".to_string()])
+        );
+        assert_eq!(loaded.locations.len(), 1);
+        assert_eq!(loaded.locations["user"].to_string(), "b.yml:1:7");
+
+        // an environment variable replaced the value, it no longer comes from the file
+        loaded.insert("user".to_string(), "root".to_string());
+        assert!(loaded.locations.is_empty());
+    }
+}
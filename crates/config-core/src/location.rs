@@ -0,0 +1,101 @@
+use std::fmt;
+
+/// A position in configuration content, lines and columns are one-based.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Location {
+    /// The file, or `<string>` for in-memory content.
+    pub name: String,
+    pub line: usize,
+    pub column: usize,
+    /// The text of the line, rendered beneath errors.
+    pub snippet: Option<String>,
+}
+
+impl Location {
+    pub fn new(name: &str, line: usize, column: usize) -> Self {
+        Location {
+            name: name.to_string(),
+            line: line.max(1),
+            column: column.max(1),
+            snippet: None,
+        }
+    }
+
+    /// Location of `line` and `column` in `content`, keeping the line as snippet.
+    pub fn in_content(name: &str, content: &str, line: usize, column: usize) -> Self {
+        let mut location = Location::new(name, line, column);
+        location.snippet = content
+            .lines()
+            .nth(location.line - 1)
+            .map(|text| text.trim_end_matches('\r').to_string());
+        location
+    }
+
+    /// Location of the byte `offset` in `content`, columns count characters.
+    pub fn from_offset(name: &str, content: &str, offset: usize) -> Self {
+        let mut offset = offset.min(content.len());
+        while !content.is_char_boundary(offset) {
+            offset -= 1;
+        }
+
+        let before = &content[..offset];
+        let line = before.matches('\n').count() + 1;
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        let column = before[line_start..].chars().count() + 1;
+        Location::in_content(name, content, line, column)
+    }
+
+    /// Render the snippet with a marker under the column, like compiler diagnostics.
+    pub(crate) fn render_snippet(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let Some(snippet) = &self.snippet else {
+            return Ok(());
+        };
+
+        let gutter = " ".repeat(self.line.to_string().len());
+        let marker_indent: String = snippet
+            .chars()
+            .take(self.column - 1)
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+        write!(
+            f,
+            "\n{} |\n{} | {}\n{} | {}^",
+            gutter, self.line, snippet, gutter, marker_indent
+        )
+    }
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}:{}", self.name, self.line, self.column)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_offset() {
+        let content = "[server]\nport = \"abc\"\n";
+        let location = Location::from_offset("config.toml", content, 16);
+        assert_eq!(location.line, 2);
+        assert_eq!(location.column, 8);
+        assert_eq!(location.snippet.as_deref(), Some("port = \"abc\""));
+        assert_eq!(location.to_string(), "config.toml:2:8");
+    }
+
+    #[test]
+    fn test_from_offset_counts_characters() {
+        let location = Location::from_offset("config.toml", "name = \"é\" x", 12);
+        assert_eq!(location.column, 12);
+    }
+
+    #[test]
+    fn test_in_content_out_of_range() {
+        let location = Location::in_content("config.ini", "a = 1", 3, 0);
+        assert_eq!(location.line, 3);
+        assert_eq!(location.column, 1);
+        assert_eq!(location.snippet, None);
+    }
+}
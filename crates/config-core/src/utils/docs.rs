@@ -0,0 +1,105 @@
+//! Reference documentation of the environment variables read by an `#[env]` struct
+use crate::utils::fields::ConfigField;
+use crate::utils::override_env::env_var_name;
+
+/// A Markdown table of the environment variables overriding `fields`
+///
+/// Variables are named like [`crate::merge_with_env_uppercase`] looks them up, e.g.
+/// `database.host` is `DATABASE_HOST`. Fields with `no_env_override` are left out, and the
+/// defaults of secrets are not shown. Unless the struct is loaded from the environment itself,
+/// a note below the table says that, like [`crate::merge_with_env_uppercase`], a variable only
+/// replaces a key that is loaded.
+///
+/// # Arguments
+///
+/// * `fields` - The fields of the struct, see the generated `config_fields()`
+/// * `from_env` - Whether the struct is loaded by `EnvConfig`, where a variable sets any key
+pub fn render_env_docs(fields: &[ConfigField], from_env: bool) -> String {
+    let mut out = String::from(
+        "| Variable | Type | Default | Required | Description |\n\
+         | --- | --- | --- | --- | --- |\n",
+    );
+    for field in fields.iter().filter(|field| field.env_override) {
+        let default = match field.default {
+            Some(_) if field.secret => String::new(),
+            Some(default) => format!("`{}`", cell(default)),
+            None => String::new(),
+        };
+        out.push_str(&format!(
+            "| `{}` | `{}` | {} | {} | {} |\n",
+            env_var_name(field.key, None),
+            cell(field.type_name),
+            default,
+            if field.required { "yes" } else { "no" },
+            cell(field.doc.unwrap_or_default()),
+        ));
+    }
+    if !from_env {
+        out.push_str(ENV_DOCS_NOTE);
+    }
+    out
+}
+
+/// The note below the table, the variables do not add keys missing from the loaded files
+const ENV_DOCS_NOTE: &str = "\nA variable only overrides a key that is set in the loaded \
+                             configuration, it does not add a missing key.\n";
+
+/// `text` on one line with `|` escaped, so it stays in its cell
+fn cell(text: &str) -> String {
+    let lines: Vec<&str> = text.lines().map(str::trim).collect();
+    lines.join(" ").replace('|', "\\|")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::fields::{Constraints, ValueType};
+
+    #[test]
+    fn test_render_env_docs() {
+        let field = |key, default| ConfigField {
+            key,
+            type_name: "String",
+            value_type: ValueType::String,
+            default,
+            required: false,
+            doc: None,
+            constraints: Constraints::default(),
+            secret: false,
+            env_override: true,
+        };
+        let fields = [
+            ConfigField {
+                required: true,
+                doc: Some("Host of the primary,\nwithout | port"),
+                ..field("database.host", None)
+            },
+            ConfigField {
+                secret: true,
+                ..field("database.password", Some("postgres"))
+            },
+            ConfigField {
+                env_override: false,
+                ..field("name", Some("app"))
+            },
+        ];
+
+        assert_eq!(
+            render_env_docs(&fields, false),
+            "| Variable | Type | Default | Required | Description |\n\
+             | --- | --- | --- | --- | --- |\n\
+             | `DATABASE_HOST` | `String` |  | yes | Host of the primary, without \\| port |\n\
+             | `DATABASE_PASSWORD` | `String` |  | no |  |\n\
+             \n\
+             A variable only overrides a key that is set in the loaded configuration, \
+             it does not add a missing key.\n"
+        );
+        assert_eq!(
+            render_env_docs(&fields, true),
+            "| Variable | Type | Default | Required | Description |\n\
+             | --- | --- | --- | --- | --- |\n\
+             | `DATABASE_HOST` | `String` |  | yes | Host of the primary, without \\| port |\n\
+             | `DATABASE_PASSWORD` | `String` |  | no |  |\n"
+        );
+    }
+}
@@ -0,0 +1,128 @@
+//! What an `#[env]` struct reads, for checks, schemas and documentation generated from the struct
+use crate::error::Error;
+use crate::location::Location;
+use std::collections::HashMap;
+
+/// A key read by a field, see the generated `config_fields()`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigField {
+    /// The key, with the prefix
+    pub key: &'static str,
+    /// The field type as written, without `Option`
+    pub type_name: &'static str,
+    pub value_type: ValueType,
+    /// `default = "..."` as written
+    pub default: Option<&'static str>,
+    /// Whether the field has no default and is not an `Option`
+    pub required: bool,
+    /// The doc comment of the field
+    pub doc: Option<&'static str>,
+    pub constraints: Constraints,
+    /// Set with `#[conf(secret)]`, the default is left out of schemas and samples
+    pub secret: bool,
+    /// Whether an environment variable can override the key, `false` with `no_env_override`
+    pub env_override: bool,
+}
+
+/// The kind of value a field type parses from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueType {
+    String,
+    Integer,
+    Number,
+    Boolean,
+    /// A custom `FromStr` type
+    Any,
+}
+
+impl ValueType {
+    /// The JSON Schema `type`, `None` for [`ValueType::Any`]
+    pub fn json_type(self) -> Option<&'static str> {
+        match self {
+            ValueType::String => Some("string"),
+            ValueType::Integer => Some("integer"),
+            ValueType::Number => Some("number"),
+            ValueType::Boolean => Some("boolean"),
+            ValueType::Any => None,
+        }
+    }
+}
+
+/// Checks of a loaded value, `#[conf(min = "1", max = "65535")]` and `#[conf(one_of("a", "b"))]`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Constraints {
+    /// The smallest number allowed
+    pub min: Option<f64>,
+    /// The largest number allowed
+    pub max: Option<f64>,
+    /// The values allowed, any if empty
+    pub one_of: &'static [&'static str],
+}
+
+impl Constraints {
+    /// Check the loaded `value` of `key`
+    ///
+    /// Values that are not numbers pass `min` and `max`, converting them reports the error.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ValueError` with the location of `key` if a check fails
+    pub fn check(
+        &self,
+        key: &str,
+        value: &str,
+        locations: &HashMap<String, Location>,
+    ) -> Result<(), Error> {
+        let number = value.trim().parse::<f64>().ok();
+        let expected = if !self.one_of.is_empty() && !self.one_of.contains(&value) {
+            let values: Vec<_> = self.one_of.iter().map(|v| format!("'{}'", v)).collect();
+            Some(format!("one of {}", values.join(", ")))
+        } else {
+            match (number, self.min, self.max) {
+                (Some(n), Some(min), _) if n < min => Some(format!("a value >= {}", min)),
+                (Some(n), _, Some(max)) if n > max => Some(format!("a value <= {}", max)),
+                _ => None,
+            }
+        };
+
+        match expected {
+            Some(expected) => Err(Error::value_conversion_error(key, &expected, value)
+                .with_location(locations.get(key).cloned())),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_constraints_check() {
+        let constraints = Constraints {
+            min: Some(1.0),
+            max: Some(100.0),
+            one_of: &[],
+        };
+        let mut locations = HashMap::new();
+        locations.insert("port".to_string(), Location::new("config.toml", 3, 8));
+
+        assert!(constraints.check("port", "1", &locations).is_ok());
+        assert!(constraints.check("port", "abc", &locations).is_ok());
+        let err = constraints.check("port", "0", &locations).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "config.toml:3:8: Invalid value for key 'port': expected a value >= 1, got '0'"
+        );
+
+        let constraints = Constraints {
+            one_of: &["debug", "info"],
+            ..Constraints::default()
+        };
+        let err = constraints.check("level", "trace", &locations).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid value for key 'level': expected one of 'debug', 'info', got 'trace'"
+        );
+    }
+}
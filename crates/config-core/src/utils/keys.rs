@@ -0,0 +1,337 @@
+//! Checks of the loaded keys against the keys a struct declares
+use crate::error::Error;
+use crate::location::Location;
+use crate::utils::warning::{warn, Warning};
+use std::collections::HashMap;
+use std::fmt;
+
+/// A loaded key that no field reads, e.g. a typo like `databse.host`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownKey {
+    pub key: String,
+    /// The closest declared key, if one is close enough to be a likely typo
+    pub suggestion: Option<String>,
+    /// Where the key is defined, if the format reports it
+    pub location: Option<Location>,
+}
+
+impl UnknownKey {
+    fn message(&self) -> String {
+        match &self.suggestion {
+            Some(suggestion) => {
+                format!("unknown key '{}', did you mean '{}'?", self.key, suggestion)
+            }
+            None => format!("unknown key '{}'", self.key),
+        }
+    }
+}
+
+impl fmt::Display for UnknownKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(location) = &self.location {
+            write!(f, "{}: ", location)?;
+        }
+        write!(f, "{}", self.message())
+    }
+}
+
+/// Find the loaded keys under `prefix` that none of the `known` keys covers
+///
+/// A known key also covers the keys nested beneath it, e.g. `servers` covers `servers[0].host`.
+///
+/// # Arguments
+///
+/// * `params` - The loaded key/value map
+/// * `known` - The keys the struct declares, with their prefix
+/// * `prefix` - Only keys starting with the prefix are checked, all keys without one
+/// * `locations` - Where the loaded keys are defined, see [`crate::Loaded::locations`]
+///
+/// # Returns
+///
+/// The unknown keys sorted by name, with the closest known key as suggestion
+pub fn unknown_keys(
+    params: &HashMap<String, String>,
+    known: &[String],
+    prefix: Option<&str>,
+    locations: &HashMap<String, Location>,
+) -> Vec<UnknownKey> {
+    let mut unknown: Vec<UnknownKey> = params
+        .keys()
+        .filter(|key| prefix.is_none_or(|p| key.starts_with(p)))
+        .filter(|key| !known.iter().any(|k| covers(k, key)))
+        .map(|key| UnknownKey {
+            key: key.clone(),
+            suggestion: suggest(key, known).map(str::to_string),
+            location: locations.get(key).cloned(),
+        })
+        .collect();
+    unknown.sort_by(|a, b| a.key.cmp(&b.key));
+    unknown
+}
+
+/// Report the unknown keys to the warning sink, see [`crate::set_warning_sink`]
+pub fn warn_unknown_keys(keys: &[UnknownKey]) {
+    for key in keys {
+        warn(Warning {
+            key: key.key.clone(),
+            message: key.message(),
+            location: key.location.clone(),
+        });
+    }
+}
+
+/// Read `key` from one of its old names when it is not set, warning about every old name in use
+///
+/// Old names are looked up in the loaded keys, then as environment variables.
+///
+/// # Arguments
+///
+/// * `params` - The loaded key/value map, the value of an old name is inserted under `key`
+/// * `key` - The current key, with the prefix
+/// * `aliases` - The old names, as written
+/// * `locations` - Where the loaded keys are defined, an old name's location is copied to `key`
+///
+/// # Errors
+///
+/// Returns `Error::ValidationError` if names of the same key are set to different values
+pub fn resolve_aliases(
+    params: &mut HashMap<String, String>,
+    key: &str,
+    aliases: &[&str],
+    locations: &mut HashMap<String, Location>,
+) -> Result<(), Error> {
+    let mut current = params
+        .get(key)
+        .map(|value| (key.to_string(), value.clone()));
+
+    for alias in aliases {
+        let value = match params.get(*alias) {
+            Some(value) => value.clone(),
+            None => match std::env::var(alias) {
+                Ok(value) => value,
+                Err(_) => continue,
+            },
+        };
+        let location = locations.get(*alias).cloned();
+        warn(Warning {
+            key: alias.to_string(),
+            message: format!("key '{}' is deprecated, use '{}'", alias, key),
+            location: location.clone(),
+        });
+
+        match &current {
+            Some((name, existing)) if *existing != value => {
+                return Err(Error::ValidationError {
+                    message: format!(
+                        "conflicting values for '{}': '{}' from '{}' and '{}' from '{}'",
+                        key, existing, name, value, alias
+                    ),
+                });
+            }
+            Some(_) => {}
+            None => {
+                params.insert(key.to_string(), value.clone());
+                if let Some(location) = location {
+                    locations.insert(key.to_string(), location);
+                }
+                current = Some((alias.to_string(), value));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Warn that `key` is deprecated if it is set
+///
+/// # Arguments
+///
+/// * `note` - What to do instead, e.g. `use db.host`
+pub fn warn_deprecated(
+    params: &HashMap<String, String>,
+    key: &str,
+    note: &str,
+    locations: &HashMap<String, Location>,
+) {
+    if params.contains_key(key) {
+        warn(Warning {
+            key: key.to_string(),
+            message: format!("key '{}' is deprecated: {}", key, note),
+            location: locations.get(key).cloned(),
+        });
+    }
+}
+
+fn covers(known: &str, key: &str) -> bool {
+    match key.strip_prefix(known) {
+        Some(rest) => rest.is_empty() || rest.starts_with('.') || rest.starts_with('['),
+        None => false,
+    }
+}
+
+/// The candidate closest to `key`, if it is within a third of its length in edits
+pub fn suggest<'a>(key: &str, candidates: &'a [String]) -> Option<&'a str> {
+    candidates
+        .iter()
+        .map(|candidate| (levenshtein(key, candidate), candidate))
+        .filter(|(distance, candidate)| *distance <= (candidate.chars().count() / 3).max(1))
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate.as_str())
+}
+
+/// The number of single character insertions, deletions and substitutions turning `a` into `b`
+pub fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+
+    for (i, ca) in a.chars().enumerate() {
+        current[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::warning::{reset_warning_sink, set_warning_sink};
+    use serial_test::serial;
+    use std::sync::{Arc, Mutex};
+
+    fn known() -> Vec<String> {
+        vec![
+            "database.host".to_string(),
+            "database.port".to_string(),
+            "servers".to_string(),
+        ]
+    }
+
+    fn collect_warnings() -> Arc<Mutex<Vec<String>>> {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let sink_seen = seen.clone();
+        set_warning_sink(move |w| sink_seen.lock().unwrap().push(w.to_string()));
+        seen
+    }
+
+    fn params(entries: &[(&str, &str)]) -> HashMap<String, String> {
+        entries
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    #[serial]
+    fn test_resolve_aliases() {
+        let seen = collect_warnings();
+        let mut locations = HashMap::new();
+        locations.insert(
+            "database.host".to_string(),
+            Location::new("config.toml", 2, 8),
+        );
+
+        let mut loaded = params(&[("database.host", "db")]);
+        resolve_aliases(
+            &mut loaded,
+            "db.host",
+            &["database.host", "DB_HOST"],
+            &mut locations,
+        )
+        .unwrap();
+        assert_eq!(loaded["db.host"], "db");
+        assert_eq!(locations["db.host"].line, 2);
+
+        let mut loaded = params(&[("db.host", "db"), ("database.host", "db")]);
+        resolve_aliases(&mut loaded, "db.host", &["database.host"], &mut locations).unwrap();
+
+        let mut loaded = params(&[("db.host", "db"), ("database.host", "old")]);
+        let err = resolve_aliases(&mut loaded, "db.host", &["database.host"], &mut locations)
+            .unwrap_err();
+        assert!(matches!(err, Error::ValidationError { .. }));
+
+        let mut loaded = params(&[("db.host", "db")]);
+        resolve_aliases(&mut loaded, "db.host", &["database.host"], &mut locations).unwrap();
+        reset_warning_sink();
+
+        assert_eq!(
+            *seen.lock().unwrap(),
+            [
+                "config.toml:2:8: key 'database.host' is deprecated, use 'db.host'",
+                "config.toml:2:8: key 'database.host' is deprecated, use 'db.host'",
+                "config.toml:2:8: key 'database.host' is deprecated, use 'db.host'",
+            ]
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn test_warn_deprecated() {
+        let seen = collect_warnings();
+        warn_deprecated(
+            &params(&[("timeout", "5")]),
+            "timeout",
+            "use timeout_ms",
+            &HashMap::new(),
+        );
+        warn_deprecated(&params(&[]), "retries", "remove it", &HashMap::new());
+        reset_warning_sink();
+
+        assert_eq!(
+            *seen.lock().unwrap(),
+            ["key 'timeout' is deprecated: use timeout_ms"]
+        );
+    }
+
+    #[test]
+    fn test_levenshtein() {
+        assert_eq!(levenshtein("", ""), 0);
+        assert_eq!(levenshtein("databse", "database"), 1);
+        assert_eq!(levenshtein("kitten", "sitting"), 3);
+        assert_eq!(levenshtein("host", ""), 4);
+    }
+
+    #[test]
+    fn test_suggest() {
+        let known = known();
+        assert_eq!(suggest("databse.host", &known), Some("database.host"));
+        assert_eq!(suggest("database.prot", &known), Some("database.port"));
+        assert_eq!(suggest("logging.level", &known), None);
+    }
+
+    #[test]
+    fn test_unknown_keys() {
+        let params: HashMap<String, String> = [
+            ("database.host", "db"),
+            ("databse.host", "db"),
+            ("servers[0].host", "a"),
+            ("serverss", "b"),
+            ("other.key", "c"),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+        let mut locations = HashMap::new();
+        locations.insert(
+            "databse.host".to_string(),
+            Location::new("config.toml", 2, 8),
+        );
+
+        let unknown = unknown_keys(&params, &known(), None, &locations);
+        let keys: Vec<_> = unknown.iter().map(|k| k.key.as_str()).collect();
+        assert_eq!(keys, ["databse.host", "other.key", "serverss"]);
+        assert_eq!(
+            unknown[0].to_string(),
+            "config.toml:2:8: unknown key 'databse.host', did you mean 'database.host'?"
+        );
+        assert_eq!(unknown[1].to_string(), "unknown key 'other.key'");
+
+        let unknown = unknown_keys(&params, &known(), Some("database."), &locations);
+        assert!(unknown.is_empty());
+    }
+}
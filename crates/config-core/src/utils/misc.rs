@@ -1,20 +1,79 @@
 /// Common utilities for configuration handling
 use crate::error::Error;
+use crate::location::Location;
+use crate::utils::path_policy::PathPolicy;
+use std::collections::HashMap;
+use std::fs;
+use std::io::Read;
 use std::path::Path;
+use std::str::FromStr;
+
+/// Suffix that marks a target file as optional, e.g. `config.local.toml?`
+///
+/// A trailing `?` is always this marker, a `?` anywhere else is a glob wildcard. Write `[?]`
+/// to match a literal `?`, e.g. `what[?].toml` or `what[?]` for a name ending with one.
+pub const OPTIONAL_SUFFIX: char = '?';
+
+/// Name reported in errors for configuration loaded from a string or a reader
+pub const INLINE_SOURCE: &str = "<string>";
+
+/// Convert the loaded value of `key`, failing with a `ValueError` that points at `locations[key]`
+///
+/// # Arguments
+///
+/// * `key` - The key the value was loaded from
+/// * `value` - The loaded value
+/// * `locations` - Where the loaded keys are defined, see [`crate::Loaded::locations`]
+///
+/// # Errors
+///
+/// Returns `Error::ValueError` if `value` cannot be parsed as `T`
+pub fn parse_value<T: FromStr>(
+    key: &str,
+    value: &str,
+    locations: &HashMap<String, Location>,
+) -> Result<T, Error> {
+    value.parse::<T>().map_err(|_| {
+        Error::value_conversion_error(key, &short_type_name::<T>(), value)
+            .with_location(locations.get(key).cloned())
+    })
+}
+
+/// `std::any::type_name` without module paths, e.g. `Vec<String>` instead of
+/// `alloc::vec::Vec<alloc::string::String>`
+fn short_type_name<T>() -> String {
+    let full = std::any::type_name::<T>();
+    let mut short = String::with_capacity(full.len());
+    let mut segment = String::new();
+    for c in full.chars() {
+        if c.is_alphanumeric() || c == '_' || c == ':' {
+            segment.push(c);
+        } else {
+            short.push_str(segment.rsplit("::").next().unwrap_or_default());
+            segment.clear();
+            short.push(c);
+        }
+    }
+    short.push_str(segment.rsplit("::").next().unwrap_or_default());
+    short
+}
 
 /// Validate and split a comma-separated list of file paths
 ///
+/// Entries may be plain files, directories or glob patterns such as `conf.d/*.toml`.
+///
 /// # Arguments
 ///
 /// * `paths_str` - A comma-separated list of file paths
 ///
 /// # Returns
 ///
-/// A vector of validated file paths
+/// A vector of validated file paths. Optional paths keep their trailing `?`,
+/// use [`split_optional`] to strip it.
 ///
 /// # Errors
 ///
-/// Returns an error if the input is empty, contains invalid paths, or exceeds the maximum path length
+/// Returns an error if the input is empty or contains invalid paths
 pub fn validate_and_split_paths(paths_str: &str) -> Result<Vec<String>, Error> {
     if paths_str.trim().is_empty() {
         return Err(Error::invalid_path(paths_str, "path cannot be empty"));
@@ -30,35 +89,197 @@ pub fn validate_and_split_paths(paths_str: &str) -> Result<Vec<String>, Error> {
         return Err(Error::invalid_path(paths_str, "no valid file paths found"));
     }
 
-    // Basic path validation
+    // Basic path validation, access control is left to the path policy
     for path in &file_paths {
-        if path.contains("..") {
-            return Err(Error::invalid_path(path, "path traversal not allowed"));
+        let (path, _) = split_optional(path);
+        if path.is_empty() {
+            return Err(Error::invalid_path(paths_str, "path cannot be empty"));
         }
-        if path.len() > 260 {
-            return Err(Error::invalid_path(path, "path too long"));
+        if path.contains('\0') {
+            return Err(Error::invalid_path(path, "invalid characters in path"));
         }
-        // Check for invalid characters (Windows-compatible)
-        if cfg!(windows) {
-            // On Windows, allow colon for drive letters but reject other invalid chars
-            if path
-                .chars()
-                .any(|c| matches!(c, '<' | '>' | '"' | '|' | '?' | '*'))
-            {
-                return Err(Error::invalid_path(path, "invalid characters in path"));
+    }
+
+    Ok(file_paths)
+}
+
+/// Split the optional marker off a target path
+///
+/// # Arguments
+///
+/// * `path` - A single target path, e.g. `config.local.toml?`
+///
+/// # Returns
+///
+/// The path without the marker and whether the marker was present
+pub fn split_optional(path: &str) -> (&str, bool) {
+    match path.strip_suffix(OPTIONAL_SUFFIX) {
+        Some(path) => (path.trim_end(), true),
+        None => (path, false),
+    }
+}
+
+/// Check if a path is a glob pattern rather than a plain file path
+///
+/// # Arguments
+///
+/// * `path` - A single target path without optional marker
+///
+/// # Returns
+///
+/// `true` if the path contains a `*` or `?` wildcard, including the `[?]` escape of a
+/// literal `?`
+pub fn is_glob_pattern(path: &str) -> bool {
+    path.contains(['*', '?'])
+}
+
+/// Expand a glob pattern or a directory into the files it contains
+///
+/// Glob patterns keep every matching file, directories keep the files whose extension
+/// is listed in `extensions` (all files if `extensions` is empty). Plain file paths are
+/// returned unchanged.
+///
+/// # Arguments
+///
+/// * `path` - A single target path without optional marker
+/// * `extensions` - File extensions accepted when expanding a directory, e.g. `["yml", "yaml"]`
+///
+/// # Returns
+///
+/// The expanded file paths in lexical order
+///
+/// # Errors
+///
+/// Returns an error if the glob pattern is invalid, or a directory or one of its entries
+/// cannot be read
+pub fn expand_target_path(path: &str, extensions: &[&str]) -> Result<Vec<String>, Error> {
+    let mut files: Vec<String> = if is_glob_pattern(path) {
+        let mut files = Vec::new();
+        for entry in glob::glob(path).map_err(|e| Error::invalid_path(path, &e.to_string()))? {
+            let entry = entry.map_err(|e| Error::IoError {
+                operation: format!("expand glob '{}'", path),
+                source: Some(Box::new(e)),
+            })?;
+            if entry.is_file() {
+                files.push(entry.to_string_lossy().into_owned());
             }
-        } else {
-            // On Unix-like systems, reject colon and other invalid chars
-            if path
-                .chars()
-                .any(|c| matches!(c, '<' | '>' | ':' | '"' | '|' | '?' | '*'))
-            {
-                return Err(Error::invalid_path(path, "invalid characters in path"));
+        }
+        files
+    } else if Path::new(path).is_dir() {
+        let read_dir_error = |e| Error::IoError {
+            operation: format!("read directory '{}'", path),
+            source: Some(Box::new(e)),
+        };
+        fs::read_dir(path)
+            .map_err(read_dir_error)?
+            .map(|entry| entry.map(|entry| entry.path()).map_err(read_dir_error))
+            .collect::<Result<Vec<_>, Error>>()?
+            .into_iter()
+            .filter(|p| p.is_file())
+            .filter(|p| {
+                extensions.is_empty()
+                    || p.extension()
+                        .and_then(|ext| ext.to_str())
+                        .is_some_and(|ext| extensions.iter().any(|e| e.eq_ignore_ascii_case(ext)))
+            })
+            .map(|p| p.to_string_lossy().into_owned())
+            .collect()
+    } else {
+        return Ok(vec![path.to_string()]);
+    };
+
+    files.sort();
+    Ok(files)
+}
+
+/// Resolve a comma-separated target into the files that should be loaded, in order
+///
+/// Paths ending with `?` are optional and are skipped when they do not exist. Glob
+/// patterns and directories are expanded in lexical order at their position in the
+/// target, so later entries still take precedence over earlier ones. Any path is accepted,
+/// see [`resolve_target_files_with_policy`] to restrict them.
+///
+/// # Arguments
+///
+/// * `paths_str` - A comma-separated list of file paths, e.g. `config.toml,conf.d/*.toml,config.local.toml?`
+/// * `extensions` - File extensions accepted when expanding a directory
+///
+/// # Returns
+///
+/// A vector of existing file paths without optional markers
+///
+/// # Errors
+///
+/// Returns an error if the target is invalid, a required file is not accessible or a
+/// required glob pattern or directory does not contain any file
+pub fn resolve_target_files(paths_str: &str, extensions: &[&str]) -> Result<Vec<String>, Error> {
+    resolve_target_files_with_policy(paths_str, extensions, &PathPolicy::permissive())
+}
+
+/// Resolve a comma-separated target into the files that should be loaded, in order,
+/// checking every file against `policy`
+///
+/// See [`resolve_target_files`] for the target syntax.
+///
+/// # Errors
+///
+/// Returns an error if the target is invalid, a file is rejected by `policy`, a required
+/// file is not accessible or a required glob pattern or directory does not contain any file
+pub fn resolve_target_files_with_policy(
+    paths_str: &str,
+    extensions: &[&str],
+    policy: &PathPolicy,
+) -> Result<Vec<String>, Error> {
+    let mut files = Vec::new();
+
+    for entry in validate_and_split_paths(paths_str)? {
+        let (path, optional) = split_optional(&entry);
+
+        if is_glob_pattern(path) || Path::new(path).is_dir() {
+            let expanded = expand_target_path(path, extensions)?;
+            if expanded.is_empty() && !optional {
+                return Err(Error::no_files_matched(path));
             }
+            for file in &expanded {
+                policy.check(file)?;
+            }
+            files.extend(expanded);
+            continue;
+        }
+
+        policy.check(path)?;
+        if optional && !Path::new(path).exists() {
+            continue;
         }
+        check_file_accessibility(path)?;
+        files.push(path.to_string());
     }
 
-    Ok(file_paths)
+    Ok(files)
+}
+
+/// Read a whole configuration source into a string
+///
+/// # Arguments
+///
+/// * `reader` - The source, e.g. `std::io::stdin()` or an opened file
+///
+/// # Returns
+///
+/// The content of the source
+///
+/// # Errors
+///
+/// Returns an error if the source cannot be read or is not valid UTF-8
+pub fn read_source<R: Read>(mut reader: R) -> Result<String, Error> {
+    let mut content = String::new();
+    reader
+        .read_to_string(&mut content)
+        .map_err(|e| Error::IoError {
+            operation: format!("read {}", INLINE_SOURCE),
+            source: Some(Box::new(e)),
+        })?;
+    Ok(content)
 }
 
 /// Check if a file exists and is readable
@@ -112,7 +333,63 @@ pub fn safe_string_to_number<T: std::str::FromStr>(
 #[cfg(test)]
 mod tests {
     use crate::error::Error;
+    use crate::location::Location;
     use crate::misc;
+    use crate::utils::path_policy::PathPolicy;
+    use std::collections::HashMap;
+    use std::fs;
+    use std::path::Path;
+
+    #[test]
+    fn test_parse_value() {
+        let mut locations = HashMap::new();
+        locations.insert(
+            "database.port".to_string(),
+            Location::in_content("config.toml", "[database]\nport = \"abc\"", 2, 8),
+        );
+
+        let port: u16 = misc::parse_value("database.port", "5432", &locations).unwrap();
+        assert_eq!(port, 5432);
+
+        let err = misc::parse_value::<u16>("database.port", "abc", &locations).unwrap_err();
+        assert_eq!(
+            err.location().map(|l| l.to_string()).as_deref(),
+            Some("config.toml:2:8")
+        );
+        assert_eq!(
+            format!("{:#}", err),
+            "config.toml:2:8: Invalid value for key 'database.port': expected u16, got 'abc'\n  |\n2 | port = \"abc\"\n  |        ^"
+        );
+
+        let err = misc::parse_value::<bool>("DEBUG", "yes", &HashMap::new()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid value for key 'DEBUG': expected bool, got 'yes'"
+        );
+        assert!(err.location().is_none());
+    }
+
+    #[test]
+    fn test_short_type_name() {
+        assert_eq!(misc::short_type_name::<String>(), "String");
+        assert_eq!(
+            misc::short_type_name::<HashMap<String, Vec<u8>>>(),
+            "HashMap<String, Vec<u8>>"
+        );
+    }
+
+    fn file_names(paths: &[String]) -> Vec<String> {
+        paths
+            .iter()
+            .map(|p| {
+                Path::new(p)
+                    .file_name()
+                    .unwrap()
+                    .to_string_lossy()
+                    .into_owned()
+            })
+            .collect()
+    }
 
     #[test]
     fn test_validate_and_split_paths_valid() {
@@ -133,17 +410,18 @@ mod tests {
 
     #[test]
     fn test_validate_and_split_paths_path_traversal() {
-        let result = misc::validate_and_split_paths("../config.json");
-        assert!(result.is_err());
-        match result.unwrap_err() {
-            Error::InvalidPathError { .. } => {}
-            _ => panic!("Expected InvalidPathError"),
-        }
+        // traversal is allowed by default, sandboxing is done by the path policy
+        let result =
+            misc::validate_and_split_paths("../config.json,/etc/app/../shared/config.toml");
+        assert_eq!(
+            result.unwrap(),
+            vec!["../config.json", "/etc/app/../shared/config.toml"]
+        );
     }
 
     #[test]
     fn test_validate_and_split_paths_invalid_chars() {
-        let result = misc::validate_and_split_paths("config<test>.json");
+        let result = misc::validate_and_split_paths("config\0.json");
         assert!(result.is_err());
         match result.unwrap_err() {
             Error::InvalidPathError { .. } => {}
@@ -152,27 +430,122 @@ mod tests {
     }
 
     #[test]
-    fn test_validate_and_split_paths_windows_drive_letter() {
-        // On Windows, drive letters with colons should be allowed
-        if cfg!(windows) {
-            // Test Windows absolute path
-            let result = misc::validate_and_split_paths(r"C:\config.json");
-            assert!(result.is_ok());
-            assert_eq!(result.unwrap(), vec![r"C:\config.json"]);
+    fn test_validate_and_split_paths_colons_and_long_paths() {
+        let result = misc::validate_and_split_paths(r"C:\config.json");
+        assert_eq!(result.unwrap(), vec![r"C:\config.json"]);
 
-            // Test Windows relative path with drive
-            let result = misc::validate_and_split_paths("C:config.json");
-            assert!(result.is_ok());
-            assert_eq!(result.unwrap(), vec!["C:config.json"]);
-        } else {
-            // On Unix-like systems, colons should be rejected
-            let result = misc::validate_and_split_paths("C:config.json");
-            assert!(result.is_err());
-            match result.unwrap_err() {
-                Error::InvalidPathError { .. } => {}
-                _ => panic!("Expected InvalidPathError"),
-            }
-        }
+        let result = misc::validate_and_split_paths("/var/run/k8s:secrets/config.json");
+        assert_eq!(result.unwrap(), vec!["/var/run/k8s:secrets/config.json"]);
+
+        let long_path = format!("{}/config.toml", "nested".repeat(60));
+        let result = misc::validate_and_split_paths(&long_path);
+        assert_eq!(result.unwrap(), vec![long_path]);
+    }
+
+    #[test]
+    fn test_validate_and_split_paths_optional() {
+        let result = misc::validate_and_split_paths("file1.json, file2.json?");
+        assert_eq!(result.unwrap(), vec!["file1.json", "file2.json?"]);
+
+        let result = misc::validate_and_split_paths("?");
+        assert!(matches!(result, Err(Error::InvalidPathError { .. })));
+    }
+
+    #[test]
+    fn test_split_optional() {
+        assert_eq!(misc::split_optional("config.toml"), ("config.toml", false));
+        assert_eq!(
+            misc::split_optional("config.local.toml?"),
+            ("config.local.toml", true)
+        );
+    }
+
+    #[test]
+    fn test_resolve_target_files_skips_missing_optional() {
+        let result = misc::resolve_target_files("Cargo.toml,nonexistent.toml?", &[]);
+        assert_eq!(result.unwrap(), vec!["Cargo.toml"]);
+
+        let result = misc::resolve_target_files("Cargo.toml?", &[]);
+        assert_eq!(result.unwrap(), vec!["Cargo.toml"]);
+
+        let result = misc::resolve_target_files("Cargo.toml,nonexistent.toml", &[]);
+        assert!(matches!(result, Err(Error::LoadFileError { .. })));
+    }
+
+    #[test]
+    fn test_resolve_target_files_glob() {
+        let result = misc::resolve_target_files("../../conf.d/*.toml", &[]).unwrap();
+        assert_eq!(
+            file_names(&result),
+            vec!["10-db.toml", "20-cache.toml", "30-override.toml"]
+        );
+
+        let result = misc::resolve_target_files("../../conf.d/*.json", &[]);
+        assert!(matches!(result, Err(Error::LoadFileError { .. })));
+
+        let result = misc::resolve_target_files("../../conf.d/*.json?", &[]);
+        assert!(result.unwrap().is_empty());
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_resolve_target_files_escaped_question_mark() {
+        let dir = std::env::temp_dir().join(format!("better-config-escape-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("what?.toml"), "").unwrap();
+        fs::write(dir.join("whatx.toml"), "").unwrap();
+        let dir_str = dir.to_string_lossy();
+
+        let result = misc::resolve_target_files(&format!("{}/what?.toml", dir_str), &[]);
+        assert_eq!(
+            file_names(&result.unwrap()),
+            vec!["what?.toml", "whatx.toml"]
+        );
+
+        let result = misc::resolve_target_files(&format!("{}/what[?].toml", dir_str), &[]);
+        assert_eq!(file_names(&result.unwrap()), vec!["what?.toml"]);
+
+        let result = misc::resolve_target_files(&format!("{}/missing[?].toml?", dir_str), &[]);
+        assert!(result.unwrap().is_empty());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_resolve_target_files_directory() {
+        let result = misc::resolve_target_files("../../conf.d", &["toml"]).unwrap();
+        assert_eq!(
+            file_names(&result),
+            vec!["10-db.toml", "20-cache.toml", "30-override.toml"]
+        );
+
+        let result = misc::resolve_target_files("../../conf.d", &["md"]).unwrap();
+        assert_eq!(file_names(&result), vec!["README.md"]);
+
+        let result = misc::resolve_target_files("../../conf.d", &["json"]);
+        assert!(matches!(result, Err(Error::LoadFileError { .. })));
+    }
+
+    #[test]
+    fn test_resolve_target_files_with_policy() {
+        let policy = PathPolicy::sandboxed("src");
+        let result = misc::resolve_target_files_with_policy("src/lib.rs", &[], &policy);
+        assert_eq!(file_names(&result.unwrap()), vec!["lib.rs"]);
+
+        let result = misc::resolve_target_files_with_policy("src/../Cargo.toml", &[], &policy);
+        assert!(matches!(result, Err(Error::InvalidPathError { .. })));
+
+        let result = misc::resolve_target_files_with_policy("src/utils/*.rs,*.toml", &[], &policy);
+        assert!(matches!(result, Err(Error::InvalidPathError { .. })));
+    }
+
+    #[test]
+    fn test_read_source() {
+        let content = misc::read_source("title = \"inline\"".as_bytes()).unwrap();
+        assert_eq!(content, "title = \"inline\"");
+
+        let result = misc::read_source(&[0xff, 0xfe][..]);
+        assert!(matches!(result, Err(Error::IoError { .. })));
     }
 
     #[test]
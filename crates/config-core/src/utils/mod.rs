@@ -1,7 +1,27 @@
+pub mod docs;
 pub mod env;
+pub mod fields;
+pub mod keys;
 pub mod misc;
 pub mod override_env;
+pub mod path_policy;
+#[cfg(feature = "schema")]
+pub mod sample;
+#[cfg(feature = "schema")]
+pub mod schema;
+pub mod search;
+pub mod warning;
 
+pub use docs::*;
 pub use env::*;
+pub use fields::*;
+pub use keys::*;
 pub use misc::*;
 pub use override_env::*;
+pub use path_policy::*;
+#[cfg(feature = "schema")]
+pub use sample::*;
+#[cfg(feature = "schema")]
+pub use schema::*;
+pub use search::*;
+pub use warning::*;
@@ -1,3 +1,4 @@
+use crate::loaded::Loaded;
 use std::collections::{HashMap, HashSet};
 
 /// Merge file-based configuration with environment variables.
@@ -69,14 +70,7 @@ pub fn merge_with_env_uppercase(
             continue;
         }
 
-        // Convert key to uppercase and replace dots with underscores for env var lookup
-        let normalized_key = key.to_uppercase().replace('.', "_");
-        let env_key = match prefix {
-            Some(p) => format!("{}{}", p, normalized_key),
-            None => normalized_key,
-        };
-
-        if let Ok(env_value) = std::env::var(&env_key) {
+        if let Ok(env_value) = std::env::var(env_var_name(key, prefix)) {
             *value = env_value;
         }
     }
@@ -84,6 +78,50 @@ pub fn merge_with_env_uppercase(
     file_config
 }
 
+/// Merge loaded configuration with environment variables like [`merge_with_env_uppercase`].
+/// A variable also sets a key that was set to null, and drops the location of the key.
+///
+/// # Arguments
+/// * `loaded` - Configuration loaded from files
+/// * `prefix` - Optional prefix for environment variable lookup
+/// * `excluded_keys` - Keys that should not be overridden by env vars
+///
+/// # Returns
+/// Merged configuration with env vars taking precedence
+pub fn merge_loaded_with_env(
+    mut loaded: Loaded,
+    prefix: Option<&str>,
+    excluded_keys: &HashSet<String>,
+) -> Loaded {
+    // keys set to null are not in the values, a variable sets them again
+    let keys: Vec<String> = loaded.values.keys().chain(&loaded.nulls).cloned().collect();
+    for key in keys {
+        // the value no longer comes from the file, `insert` drops its location
+        if excluded_keys.contains(&key) {
+            continue;
+        }
+        if let Ok(env_value) = std::env::var(env_var_name(&key, prefix)) {
+            loaded.insert(key, env_value);
+        }
+    }
+
+    loaded
+}
+
+/// The environment variable overriding `key` in [`merge_with_env_uppercase`].
+/// The key is uppercased and dots become underscores, e.g. `database.host` is `DATABASE_HOST`.
+///
+/// # Arguments
+/// * `key` - Configuration key
+/// * `prefix` - Optional prefix for environment variable lookup
+pub fn env_var_name(key: &str, prefix: Option<&str>) -> String {
+    let normalized_key = key.to_uppercase().replace('.', "_");
+    match prefix {
+        Some(p) => format!("{}{}", p, normalized_key),
+        None => normalized_key,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -219,6 +257,27 @@ mod tests {
         cleanup_env_vars();
     }
 
+    #[test]
+    #[serial]
+    fn test_merge_loaded_with_env_sets_null_keys() {
+        cleanup_env_vars();
+        env::set_var("DATABASE_HOST", "env-host");
+
+        let mut loaded = Loaded::new();
+        loaded.insert_null("database.host".to_string());
+        loaded.insert_null("database.port".to_string());
+
+        let result = merge_loaded_with_env(loaded, None, &HashSet::new());
+
+        assert_eq!(
+            result.values.get("database.host"),
+            Some(&"env-host".to_string())
+        );
+        assert_eq!(result.nulls, HashSet::from(["database.port".to_string()]));
+
+        cleanup_env_vars();
+    }
+
     #[test]
     #[serial]
     fn test_merge_with_env_empty_config() {
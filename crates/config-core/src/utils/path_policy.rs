@@ -0,0 +1,191 @@
+/// Policy deciding which configuration files may be loaded
+use crate::error::Error;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+/// Policy applied to every target file before it is loaded
+///
+/// The default policy is permissive and accepts any path. A sandboxed policy only accepts
+/// paths that resolve inside its root once symlinks and `..` components are resolved.
+/// The built-in loaders use the policy of their `SANDBOX_ROOT`, set per struct with
+/// `#[env(TomlConfig(sandbox_root = "/etc/app"))]`.
+///
+/// # Example
+/// ```rust
+/// use better_config_core::utils::PathPolicy;
+///
+/// // only load configuration files below the current directory
+/// let policy = PathPolicy::sandboxed(".");
+/// assert!(policy.check("../config.toml").is_err());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PathPolicy {
+    root: Option<PathBuf>,
+}
+
+impl PathPolicy {
+    /// Accept any path.
+    pub fn permissive() -> Self {
+        Self::default()
+    }
+
+    /// Only accept paths that resolve inside `root`.
+    pub fn sandboxed(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: Some(root.into()),
+        }
+    }
+
+    /// A sandboxed policy for `Some(root)`, a permissive one for `None`, e.g. a loader's
+    /// `SANDBOX_ROOT`.
+    pub fn from_root(root: Option<&str>) -> Self {
+        Self {
+            root: root.map(PathBuf::from),
+        }
+    }
+
+    /// The sandbox root, `None` for a permissive policy.
+    pub fn root(&self) -> Option<&Path> {
+        self.root.as_deref()
+    }
+
+    /// Check whether `path` may be loaded under this policy.
+    ///
+    /// # Errors
+    /// * `Error::InvalidPathError` - If the path resolves outside of the sandbox root or the
+    ///   root itself cannot be resolved.
+    pub fn check(&self, path: &str) -> Result<(), Error> {
+        let Some(root) = &self.root else {
+            return Ok(());
+        };
+
+        let root = root.canonicalize().map_err(|e| {
+            Error::invalid_path(
+                &root.display().to_string(),
+                &format!("sandbox root cannot be resolved: {}", e),
+            )
+        })?;
+        let resolved = resolve_path(Path::new(path))
+            .map_err(|e| Error::invalid_path(path, &format!("path cannot be resolved: {}", e)))?;
+
+        if resolved.starts_with(&root) {
+            Ok(())
+        } else {
+            Err(Error::invalid_path(
+                path,
+                &format!("path is outside of sandbox root '{}'", root.display()),
+            ))
+        }
+    }
+}
+
+/// Resolve `path` to an absolute path, following symlinks for the part that exists.
+///
+/// Components are resolved one by one, so `..` applies to the target of a symlink rather than
+/// to the link itself. Components after the first missing one are normalized lexically.
+fn resolve_path(path: &Path) -> io::Result<PathBuf> {
+    let absolute = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        std::env::current_dir()?.join(path)
+    };
+
+    let mut resolved = PathBuf::new();
+    let mut missing = false;
+    for component in absolute.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir if missing => {
+                resolved.pop();
+                missing = !resolved.exists();
+            }
+            component => {
+                resolved.push(component);
+                if !missing {
+                    match resolved.canonicalize() {
+                        Ok(canonical) => resolved = canonical,
+                        Err(e) if e.kind() == io::ErrorKind::NotFound => missing = true,
+                        Err(e) => return Err(e),
+                    }
+                }
+            }
+        }
+    }
+    Ok(resolved)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_permissive_accepts_any_path() {
+        let policy = PathPolicy::permissive();
+        assert!(policy.check("../config.toml").is_ok());
+        assert!(policy.check("/etc/app/../shared/config.toml").is_ok());
+        assert!(policy
+            .check("/var/run/secrets/k8s:token/config.toml")
+            .is_ok());
+    }
+
+    #[test]
+    fn test_sandboxed_accepts_paths_inside_root() {
+        let policy = PathPolicy::sandboxed(".");
+        assert!(policy.check("Cargo.toml").is_ok());
+        assert!(policy.check("src/../Cargo.toml").is_ok());
+        assert!(policy.check("missing/config.toml").is_ok());
+    }
+
+    #[test]
+    fn test_sandboxed_rejects_paths_outside_root() {
+        let policy = PathPolicy::sandboxed("src");
+        match policy.check("src/../Cargo.toml") {
+            Err(Error::InvalidPathError { .. }) => {}
+            _ => panic!("Expected InvalidPathError"),
+        }
+        assert!(policy.check("../../outside.toml").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_sandboxed_resolves_symlinks_before_parent_dirs() {
+        let base =
+            std::env::temp_dir().join(format!("better-config-policy-{}", std::process::id()));
+        let root = base.join("root");
+        let outside = base.join("outside").join("dir");
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::create_dir_all(&outside).unwrap();
+        let link = root.join("link");
+        let _ = std::fs::remove_file(&link);
+        std::os::unix::fs::symlink(&outside, &link).unwrap();
+
+        // `root/link/..` is `outside`, not `root`
+        let policy = PathPolicy::sandboxed(&root);
+        let escaped = link.join("../missing.toml");
+        let inside = root.join("missing/../config.toml");
+        let escaped_ok = policy.check(&escaped.to_string_lossy()).is_ok();
+        let inside_ok = policy.check(&inside.to_string_lossy()).is_ok();
+        std::fs::remove_dir_all(&base).unwrap();
+
+        assert!(!escaped_ok);
+        assert!(inside_ok);
+    }
+
+    #[test]
+    fn test_from_root() {
+        assert_eq!(PathPolicy::from_root(None), PathPolicy::permissive());
+        assert_eq!(
+            PathPolicy::from_root(Some("src")),
+            PathPolicy::sandboxed("src")
+        );
+    }
+
+    #[test]
+    fn test_sandboxed_missing_root() {
+        let policy = PathPolicy::sandboxed("nonexistent-root");
+        assert!(matches!(
+            policy.check("Cargo.toml"),
+            Err(Error::InvalidPathError { .. })
+        ));
+    }
+}
@@ -0,0 +1,355 @@
+//! Documented sample configuration files written from the fields of an `#[env]` struct
+use crate::utils::fields::{ConfigField, ValueType};
+use crate::utils::override_env::env_var_name;
+use serde_json::Value;
+
+/// The value written for a `#[conf(secret)]` field instead of its default
+pub const SECRET_PLACEHOLDER: &str = "<secret>";
+
+/// The formats [`render_sample`] writes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SampleFormat {
+    Toml,
+    Json,
+    Yml,
+    Ini,
+    /// A dotenv file of the environment variables overriding the keys, e.g. `DATABASE_HOST=`
+    Env,
+}
+
+/// A sample configuration file with every key of `fields`
+///
+/// Keys are set to their default, or to the empty value of their type. Secrets are set to
+/// [`SECRET_PLACEHOLDER`], and keys without a default that are not required are commented out,
+/// or `null` in JSON. Doc comments become comments, except in JSON, which has none. Dotted keys
+/// become sections or nested objects, and the file loads back into the same keys. Env samples
+/// name the variables like [`env_var_name`] and leave out the keys with `no_env_override`.
+///
+/// # Arguments
+///
+/// * `fields` - The fields of the struct, see the generated `config_fields()`
+/// * `format` - The format of the file
+pub fn render_sample(fields: &[ConfigField], format: SampleFormat) -> String {
+    let entries: Vec<Entry> = fields.iter().map(Entry::new).collect();
+    let mut out = String::new();
+    match format {
+        SampleFormat::Toml | SampleFormat::Ini => {
+            let root = Table::build(&entries);
+            write_sections(&mut out, &root, &mut Vec::new(), format);
+        }
+        SampleFormat::Json => {
+            write_json(&mut out, &Table::build(&entries), 0);
+            out.push('\n');
+        }
+        SampleFormat::Yml => write_yaml(&mut out, &Table::build(&entries), 0),
+        SampleFormat::Env => {
+            for entry in entries.iter().filter(|entry| entry.field.env_override) {
+                write_comment(&mut out, entry.field.doc, "", "#");
+                let unset = if entry.unset { "# " } else { "" };
+                out.push_str(&format!(
+                    "{}{}={}\n",
+                    unset,
+                    env_var_name(entry.field.key, None),
+                    env_value(&entry.text())
+                ));
+            }
+        }
+    }
+    out
+}
+
+/// A field with the value written for it
+struct Entry<'a> {
+    field: &'a ConfigField,
+    value: Value,
+    /// Written commented out, the key has neither a default nor has to be set
+    unset: bool,
+}
+
+impl<'a> Entry<'a> {
+    fn new(field: &'a ConfigField) -> Self {
+        let value = if field.secret {
+            Value::String(SECRET_PLACEHOLDER.to_string())
+        } else if let Some(default) = field.default {
+            field.value_type.json_value(default)
+        } else {
+            match field.value_type {
+                ValueType::Integer => Value::from(0),
+                ValueType::Number => Value::from(0.0),
+                ValueType::Boolean => Value::Bool(false),
+                ValueType::String | ValueType::Any => Value::String(String::new()),
+            }
+        };
+        let unset = !field.secret && !field.required && field.default.is_none();
+        Entry {
+            field,
+            value,
+            unset,
+        }
+    }
+
+    /// The value as plain text, for formats without types
+    fn text(&self) -> String {
+        match &self.value {
+            Value::String(s) => s.clone(),
+            other => other.to_string(),
+        }
+    }
+
+    /// The value as a TOML, JSON or YAML scalar
+    fn literal(&self) -> String {
+        self.value.to_string()
+    }
+}
+
+/// The entries grouped by the dotted parts of their keys, in the order they are declared
+#[derive(Default)]
+struct Table<'a> {
+    entries: Vec<(&'a str, &'a Entry<'a>)>,
+    tables: Vec<(&'a str, Table<'a>)>,
+}
+
+impl<'a> Table<'a> {
+    fn build(entries: &'a [Entry<'a>]) -> Self {
+        let mut root = Table::default();
+        for entry in entries {
+            let mut parts: Vec<&str> = entry.field.key.split('.').collect();
+            let name = parts.pop().unwrap_or_default();
+            let mut table = &mut root;
+            for part in parts {
+                let index = match table.tables.iter().position(|(n, _)| *n == part) {
+                    Some(index) => index,
+                    None => {
+                        table.tables.push((part, Table::default()));
+                        table.tables.len() - 1
+                    }
+                };
+                table = &mut table.tables[index].1;
+            }
+            table.entries.push((name, entry));
+        }
+        root
+    }
+}
+
+fn write_comment(out: &mut String, doc: Option<&str>, indent: &str, marker: &str) {
+    for line in doc.into_iter().flat_map(str::lines) {
+        match line.is_empty() {
+            true => out.push_str(&format!("{}{}\n", indent, marker)),
+            false => out.push_str(&format!("{}{} {}\n", indent, marker, line)),
+        }
+    }
+}
+
+/// TOML tables or INI sections, `[database]` then `host = ...`
+fn write_sections<'a>(
+    out: &mut String,
+    table: &Table<'a>,
+    path: &mut Vec<&'a str>,
+    format: SampleFormat,
+) {
+    if !table.entries.is_empty() && !path.is_empty() {
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        let header: Vec<String> = match format {
+            SampleFormat::Toml => path.iter().map(|part| toml_key(part)).collect(),
+            _ => path.iter().map(|part| part.to_string()).collect(),
+        };
+        out.push_str(&format!("[{}]\n", header.join(".")));
+    }
+
+    for (name, entry) in &table.entries {
+        let marker = if format == SampleFormat::Toml {
+            "#"
+        } else {
+            ";"
+        };
+        write_comment(out, entry.field.doc, "", marker);
+        let (key, value) = match format {
+            SampleFormat::Toml => (toml_key(name), entry.literal()),
+            _ => (name.to_string(), entry.text()),
+        };
+        let unset = if entry.unset {
+            format!("{} ", marker)
+        } else {
+            String::new()
+        };
+        out.push_str(&format!("{}{} = {}\n", unset, key, value));
+    }
+
+    for (name, child) in &table.tables {
+        path.push(name);
+        write_sections(out, child, path, format);
+        path.pop();
+    }
+}
+
+fn write_json(out: &mut String, table: &Table, depth: usize) {
+    let indent = "  ".repeat(depth + 1);
+    let mut members = Vec::new();
+    for (name, entry) in &table.entries {
+        let key = Value::from(*name);
+        match entry.unset {
+            true => members.push(format!("{}{}: null", indent, key)),
+            false => members.push(format!("{}{}: {}", indent, key, entry.literal())),
+        }
+    }
+    for (name, child) in &table.tables {
+        let mut member = format!("{}{}: ", indent, Value::from(*name));
+        write_json(&mut member, child, depth + 1);
+        members.push(member);
+    }
+
+    out.push_str("{\n");
+    out.push_str(&members.join(",\n"));
+    if !members.is_empty() {
+        out.push('\n');
+    }
+    out.push_str(&format!("{}}}", "  ".repeat(depth)));
+}
+
+fn write_yaml(out: &mut String, table: &Table, depth: usize) {
+    let indent = "  ".repeat(depth);
+    for (name, entry) in &table.entries {
+        write_comment(out, entry.field.doc, &indent, "#");
+        let unset = if entry.unset { "# " } else { "" };
+        out.push_str(&format!(
+            "{}{}{}: {}\n",
+            indent,
+            unset,
+            yaml_key(name),
+            entry.literal()
+        ));
+    }
+    for (name, child) in &table.tables {
+        out.push_str(&format!("{}{}:\n", indent, yaml_key(name)));
+        write_yaml(out, child, depth + 1);
+    }
+}
+
+fn is_bare_key(key: &str) -> bool {
+    !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+fn toml_key(key: &str) -> String {
+    match is_bare_key(key) {
+        true => key.to_string(),
+        false => Value::from(key).to_string(),
+    }
+}
+
+fn yaml_key(key: &str) -> String {
+    let plain = is_bare_key(key)
+        && !key.starts_with('-')
+        && !matches!(
+            key.to_ascii_lowercase().as_str(),
+            "true" | "false" | "yes" | "no" | "on" | "off" | "null" | "y" | "n"
+        );
+    match plain {
+        true => key.to_string(),
+        false => Value::from(key).to_string(),
+    }
+}
+
+/// A dotenv value, quoted unless it is plain, without `$` expansion
+fn env_value(value: &str) -> String {
+    let plain = !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "_-./:@,+".contains(c));
+    if plain {
+        value.to_string()
+    } else if !value.contains(['\'', '\n']) {
+        format!("'{}'", value)
+    } else {
+        let mut quoted = String::from("\"");
+        for c in value.chars() {
+            match c {
+                '"' | '\\' | '$' => {
+                    quoted.push('\\');
+                    quoted.push(c);
+                }
+                '\n' => quoted.push_str("\\n"),
+                _ => quoted.push(c),
+            }
+        }
+        quoted.push('"');
+        quoted
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::fields::Constraints;
+
+    fn fields() -> Vec<ConfigField> {
+        let field = |key, value_type, default| ConfigField {
+            key,
+            type_name: "String",
+            value_type,
+            default,
+            required: false,
+            doc: None,
+            constraints: Constraints::default(),
+            secret: false,
+            env_override: true,
+        };
+        vec![
+            ConfigField {
+                doc: Some("Name shown in logs"),
+                ..field("name", ValueType::String, Some("app"))
+            },
+            field("debug", ValueType::Boolean, None),
+            ConfigField {
+                required: true,
+                ..field("database.host", ValueType::String, None)
+            },
+            field("database.port", ValueType::Integer, Some("5432")),
+            ConfigField {
+                secret: true,
+                ..field("database.password", ValueType::String, Some("hunter2"))
+            },
+        ]
+    }
+
+    #[test]
+    fn test_toml_sample() {
+        assert_eq!(
+            render_sample(&fields(), SampleFormat::Toml),
+            "# Name shown in logs\n\
+             name = \"app\"\n\
+             # debug = false\n\
+             \n\
+             [database]\n\
+             host = \"\"\n\
+             port = 5432\n\
+             password = \"<secret>\"\n"
+        );
+    }
+
+    #[test]
+    fn test_json_sample() {
+        assert_eq!(
+            render_sample(&fields(), SampleFormat::Json),
+            "{\n  \"name\": \"app\",\n  \"debug\": null,\n  \"database\": {\n    \"host\": \"\",\n    \"port\": 5432,\n    \"password\": \"<secret>\"\n  }\n}\n"
+        );
+        assert_eq!(render_sample(&[], SampleFormat::Json), "{\n}\n");
+    }
+
+    #[test]
+    fn test_env_sample() {
+        let mut fields = fields();
+        fields[0].env_override = false;
+        assert_eq!(
+            render_sample(&fields, SampleFormat::Env),
+            "# DEBUG=false\nDATABASE_HOST=''\nDATABASE_PORT=5432\nDATABASE_PASSWORD='<secret>'\n"
+        );
+        assert_eq!(env_value("a b$c"), "'a b$c'");
+        assert_eq!(env_value("it's $HOME"), "\"it's \\$HOME\"");
+    }
+}
@@ -0,0 +1,295 @@
+//! A JSON Schema of an `#[env]` struct, enabled with the `schema` feature
+use crate::utils::fields::{ConfigField, ValueType};
+use serde_json::{json, Map, Value};
+
+impl ValueType {
+    /// `value` as a JSON value of this type, a string if it does not parse
+    pub fn json_value(self, value: &str) -> Value {
+        let parsed = match self {
+            ValueType::Integer => value
+                .parse::<i64>()
+                .map(Value::from)
+                .or_else(|_| value.parse::<u64>().map(Value::from))
+                .ok(),
+            ValueType::Number => value.parse::<f64>().ok().map(number),
+            ValueType::Boolean => value.parse::<bool>().ok().map(Value::Bool),
+            ValueType::String | ValueType::Any => None,
+        };
+        parsed.unwrap_or_else(|| Value::String(value.to_string()))
+    }
+}
+
+/// A JSON Schema of the configuration file read by `fields`
+///
+/// Dotted keys become nested objects, e.g. `database.host` is the `host` property of `database`,
+/// and index segments become arrays, e.g. `hosts[0]` is the `items` of `hosts`. A key that is
+/// also the parent of other keys, like `server` next to `server.host`, is described as the object.
+///
+/// # Arguments
+///
+/// * `title` - The schema title, the struct name
+/// * `description` - The doc comment of the struct
+/// * `fields` - The fields of the struct, see the generated `config_fields()`
+pub fn json_schema(title: &str, description: Option<&str>, fields: &[ConfigField]) -> Value {
+    let mut root = object_schema();
+    let root_map = root.as_object_mut().expect("an object schema");
+    root_map.insert(
+        "$schema".to_string(),
+        json!("https://json-schema.org/draft/2020-12/schema"),
+    );
+    root_map.insert("title".to_string(), json!(title));
+    if let Some(description) = description {
+        root_map.insert("description".to_string(), json!(description));
+    }
+
+    for field in fields {
+        let segments = segments(field.key);
+        let Some((last, parents)) = segments.split_last() else {
+            continue;
+        };
+
+        let mut node = &mut root;
+        for (segment, next) in parents.iter().zip(&segments[1..]) {
+            let child = child(node, segment);
+            match next {
+                Segment::Property(_) if !is_object_schema(child) => *child = object_schema(),
+                Segment::Index if !is_array_schema(child) => *child = array_schema(),
+                _ => {}
+            }
+            node = child;
+        }
+
+        let leaf = child(node, last);
+        if !is_object_schema(leaf) && !is_array_schema(leaf) {
+            *leaf = field_schema(field);
+        }
+        if let (Segment::Property(name), true) = (last, field.required) {
+            if let Some(Value::Array(required)) = node.get_mut("required") {
+                if !required.contains(&json!(name)) {
+                    required.push(json!(name));
+                }
+            }
+        }
+    }
+
+    prune_required(&mut root);
+    root
+}
+
+/// A part of a key, `hosts[0].name` is `hosts`, an index and `name`
+enum Segment<'a> {
+    Property(&'a str),
+    Index,
+}
+
+fn segments(key: &str) -> Vec<Segment<'_>> {
+    let mut segments = Vec::new();
+    for part in key.split('.') {
+        let (name, indexes) = part.split_at(part.find('[').unwrap_or(part.len()));
+        if !name.is_empty() {
+            segments.push(Segment::Property(name));
+        }
+        segments.extend(indexes.matches('[').map(|_| Segment::Index));
+    }
+    segments
+}
+
+/// The schema of `segment` in the object or array schema `node`, `null` if it is new
+fn child<'a>(node: &'a mut Value, segment: &Segment) -> &'a mut Value {
+    match segment {
+        Segment::Property(name) => &mut node["properties"][*name],
+        Segment::Index => &mut node["items"],
+    }
+}
+
+fn object_schema() -> Value {
+    json!({ "type": "object", "properties": {}, "required": [] })
+}
+
+fn array_schema() -> Value {
+    json!({ "type": "array", "items": {} })
+}
+
+fn is_object_schema(node: &Value) -> bool {
+    node.get("properties").is_some_and(Value::is_object)
+}
+
+fn is_array_schema(node: &Value) -> bool {
+    node.get("type").and_then(Value::as_str) == Some("array")
+}
+
+/// Drops the empty `required` arrays
+fn prune_required(node: &mut Value) {
+    let Some(map) = node.as_object_mut() else {
+        return;
+    };
+    if map
+        .get("required")
+        .is_some_and(|r| r.as_array().is_some_and(Vec::is_empty))
+    {
+        map.remove("required");
+    }
+    if let Some(Value::Object(properties)) = map.get_mut("properties") {
+        properties.values_mut().for_each(prune_required);
+    }
+    if let Some(items) = map.get_mut("items") {
+        prune_required(items);
+    }
+}
+
+fn field_schema(field: &ConfigField) -> Value {
+    let mut schema = Map::new();
+    if let Some(json_type) = field.value_type.json_type() {
+        schema.insert("type".to_string(), json!(json_type));
+    }
+    if let Some(doc) = field.doc {
+        schema.insert("description".to_string(), json!(doc));
+    }
+    match field.default {
+        _ if field.secret => {
+            schema.insert("writeOnly".to_string(), json!(true));
+        }
+        Some(default) => {
+            schema.insert("default".to_string(), field.value_type.json_value(default));
+        }
+        None => {}
+    }
+    if let Some(min) = field.constraints.min {
+        schema.insert("minimum".to_string(), number(min));
+    }
+    if let Some(max) = field.constraints.max {
+        schema.insert("maximum".to_string(), number(max));
+    }
+    if !field.constraints.one_of.is_empty() {
+        let values = field.constraints.one_of.iter();
+        let values = values.map(|v| field.value_type.json_value(v)).collect();
+        schema.insert("enum".to_string(), Value::Array(values));
+    }
+    Value::Object(schema)
+}
+
+/// `1.0` as `1`, so integer bounds stay integers
+fn number(n: f64) -> Value {
+    if n.fract() == 0.0 && n.abs() < i64::MAX as f64 {
+        json!(n as i64)
+    } else {
+        json!(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::fields::Constraints;
+
+    fn field(key: &'static str, value_type: ValueType) -> ConfigField {
+        ConfigField {
+            key,
+            type_name: "String",
+            value_type,
+            default: None,
+            required: false,
+            doc: None,
+            constraints: Constraints::default(),
+            secret: false,
+            env_override: true,
+        }
+    }
+
+    #[test]
+    fn test_json_schema_nests_dotted_keys() {
+        let fields = [
+            ConfigField {
+                required: true,
+                doc: Some("Database host"),
+                ..field("database.host", ValueType::String)
+            },
+            ConfigField {
+                type_name: "u16",
+                default: Some("5432"),
+                constraints: Constraints {
+                    min: Some(1.0),
+                    ..Constraints::default()
+                },
+                ..field("database.port", ValueType::Integer)
+            },
+            field("debug", ValueType::Boolean),
+        ];
+
+        let schema = json_schema("AppConfig", None, &fields);
+        assert_eq!(schema["title"], "AppConfig");
+        assert!(schema.get("required").is_none());
+        assert_eq!(schema["properties"]["debug"], json!({ "type": "boolean" }));
+        assert_eq!(
+            schema["properties"]["database"],
+            json!({
+                "type": "object",
+                "required": ["host"],
+                "properties": {
+                    "host": { "type": "string", "description": "Database host" },
+                    "port": { "type": "integer", "default": 5432, "minimum": 1 },
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn test_json_schema_prefers_objects_over_leaf_keys() {
+        let fields = [
+            ConfigField {
+                required: true,
+                ..field("server", ValueType::String)
+            },
+            field("server.host", ValueType::String),
+            field("client.port", ValueType::Integer),
+            field("client", ValueType::String),
+        ];
+
+        let schema = json_schema("AppConfig", None, &fields);
+        assert_eq!(schema["required"], json!(["server"]));
+        assert_eq!(
+            schema["properties"]["server"],
+            json!({
+                "type": "object",
+                "properties": { "host": { "type": "string" } },
+            })
+        );
+        assert_eq!(
+            schema["properties"]["client"]["properties"]["port"],
+            json!({ "type": "integer" })
+        );
+    }
+
+    #[test]
+    fn test_json_schema_maps_indexes_to_arrays() {
+        let fields = [
+            field("hosts[0]", ValueType::String),
+            ConfigField {
+                required: true,
+                ..field("servers[0].port", ValueType::Integer)
+            },
+            field("matrix[0][1]", ValueType::Number),
+        ];
+
+        let schema = json_schema("AppConfig", None, &fields);
+        assert_eq!(
+            schema["properties"]["hosts"],
+            json!({ "type": "array", "items": { "type": "string" } })
+        );
+        assert_eq!(
+            schema["properties"]["servers"],
+            json!({
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "required": ["port"],
+                    "properties": { "port": { "type": "integer" } },
+                },
+            })
+        );
+        assert_eq!(
+            schema["properties"]["matrix"]["items"]["items"],
+            json!({ "type": "number" })
+        );
+    }
+}
@@ -0,0 +1,289 @@
+/// Standard locations for configuration files
+use crate::error::Error;
+use crate::loaded::Loaded;
+use crate::utils::misc::{is_glob_pattern, split_optional};
+use std::env;
+use std::path::{Path, PathBuf};
+
+/// Directories searched for the configuration files of `app`, most specific first.
+///
+/// 1. The current directory and its parents up to the repository root (the first
+///    directory containing `.git`), or only the current directory outside a repository
+/// 2. The directory of the running executable
+/// 3. `$XDG_CONFIG_HOME/<app>`
+/// 4. `~/.config/<app>`
+/// 5. `/etc/<app>` (Unix only)
+///
+/// # Example
+/// ```rust
+/// use better_config_core::utils::search_dirs;
+///
+/// let dirs = search_dirs("myapp");
+/// assert_eq!(dirs[0], std::env::current_dir().unwrap());
+/// ```
+pub fn search_dirs(app: &str) -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+
+    if let Ok(cwd) = env::current_dir() {
+        let mut ancestors = Vec::new();
+        let mut repo_root = false;
+        for dir in cwd.ancestors() {
+            ancestors.push(dir.to_path_buf());
+            if dir.join(".git").exists() {
+                repo_root = true;
+                break;
+            }
+        }
+        if !repo_root {
+            ancestors.truncate(1);
+        }
+        dirs.extend(ancestors);
+    }
+
+    if let Some(exe_dir) = env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(Path::to_path_buf))
+    {
+        dirs.push(exe_dir);
+    }
+
+    if let Some(xdg) = env::var_os("XDG_CONFIG_HOME").filter(|v| !v.is_empty()) {
+        dirs.push(PathBuf::from(xdg).join(app));
+    }
+
+    let home = if cfg!(windows) {
+        env::var_os("USERPROFILE")
+    } else {
+        env::var_os("HOME")
+    };
+    if let Some(home) = home.filter(|v| !v.is_empty()) {
+        dirs.push(PathBuf::from(home).join(".config").join(app));
+    }
+
+    if cfg!(unix) {
+        dirs.push(Path::new("/etc").join(app));
+    }
+
+    let mut unique = Vec::with_capacity(dirs.len());
+    for dir in dirs {
+        if !unique.contains(&dir) {
+            unique.push(dir);
+        }
+    }
+    unique
+}
+
+/// Find `file_name` in the [`search_dirs`] of `app`, most specific first.
+///
+/// # Arguments
+/// * `app` - The application name, e.g. `myapp`
+/// * `file_name` - The file to look for, e.g. `config.toml`, or a glob pattern such as
+///   `conf.d/*.toml`
+///
+/// # Returns
+/// The existing files, most specific first, the matches of a pattern in lexical order
+pub fn search_files(app: &str, file_name: &str) -> Vec<PathBuf> {
+    search_matches(app, file_name)
+        .into_iter()
+        .flatten()
+        .collect()
+}
+
+/// The files matching `file_name` in each search dir with a match, most specific first.
+fn search_matches(app: &str, file_name: &str) -> Vec<Vec<PathBuf>> {
+    search_dirs(app)
+        .into_iter()
+        .map(|dir| match is_glob_pattern(file_name) {
+            true => glob_files(&dir, file_name),
+            false => vec![dir.join(file_name)],
+        })
+        .map(|files| files.into_iter().filter(|path| path.is_file()).collect())
+        .filter(|files: &Vec<PathBuf>| !files.is_empty())
+        .collect()
+}
+
+/// The files matching `pattern` relative to `dir`, in lexical order.
+fn glob_files(dir: &Path, pattern: &str) -> Vec<PathBuf> {
+    let dir = glob::Pattern::escape(&dir.to_string_lossy());
+    let pattern = Path::new(&dir).join(pattern);
+    let mut files: Vec<PathBuf> = match glob::glob(&pattern.to_string_lossy()) {
+        Ok(paths) => paths.filter_map(Result::ok).collect(),
+        Err(_) => Vec::new(),
+    };
+    files.sort();
+    files
+}
+
+/// The loader targets for the files found by [`search_files`].
+///
+/// # Arguments
+/// * `app` - The application name, e.g. `myapp`
+/// * `target` - The comma-separated files to look for, a trailing `?` marks one as optional
+/// * `layered` - Use every match of a file instead of only the most specific one
+/// * `later_targets_override` - Whether the loader lets later files override earlier ones,
+///   which decides the order of layered matches
+///
+/// # Returns
+/// One target per found file, in the order of `target`. A glob pattern keeps all its
+/// matches in the most specific directory with one, or in every directory when layered.
+/// Absolute paths are not searched. A file that was not found is kept as written so that
+/// the loader reports it as missing (or skips it when optional).
+pub fn search_target(
+    app: &str,
+    target: &str,
+    layered: bool,
+    later_targets_override: bool,
+) -> Vec<String> {
+    let mut targets = Vec::new();
+    for file_name in target.split(',').map(str::trim).filter(|t| !t.is_empty()) {
+        let (name, _) = split_optional(file_name);
+        if Path::new(name).is_absolute() {
+            targets.push(file_name.to_string());
+            continue;
+        }
+
+        let mut found = search_matches(app, name);
+        if found.is_empty() {
+            targets.push(file_name.to_string());
+            continue;
+        }
+
+        if !layered {
+            found.truncate(1);
+        }
+        if later_targets_override {
+            // the most specific files must come last to win
+            found.reverse();
+        }
+        targets.extend(
+            found
+                .iter()
+                .flatten()
+                .map(|path| path.to_string_lossy().into_owned()),
+        );
+    }
+    targets
+}
+
+/// Load each of `targets` on its own and merge the results, later targets win.
+///
+/// Keys a later target sets to null drop the values of earlier ones, see [`Loaded::merge`].
+///
+/// Found paths are passed to the loader one by one, so a comma in a path is not read as a
+/// separator.
+///
+/// # Errors
+/// Returns the first error of `load`
+pub fn load_targets<F>(targets: Vec<String>, mut load: F) -> Result<Loaded, Error>
+where
+    F: FnMut(Option<String>) -> Result<Loaded, Error>,
+{
+    let mut loaded = Loaded::new();
+    for target in targets {
+        loaded.merge(load(Some(target))?);
+    }
+    Ok(loaded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_search_dirs_starts_with_current_dir() {
+        let dirs = search_dirs("better-config-test");
+        assert_eq!(dirs[0], env::current_dir().unwrap());
+        if cfg!(unix) {
+            assert_eq!(
+                dirs.last().unwrap(),
+                &Path::new("/etc").join("better-config-test")
+            );
+        }
+    }
+
+    #[test]
+    fn test_search_files_walks_up_to_repo_root() {
+        // the crate manifest is found before the workspace manifest
+        let found = search_files("better-config-test", "Cargo.toml");
+        let cwd = env::current_dir().unwrap();
+        assert_eq!(found[0], cwd.join("Cargo.toml"));
+        if cwd.ancestors().any(|dir| dir.join(".git").exists()) {
+            assert!(found.len() > 1);
+        }
+    }
+
+    #[test]
+    fn test_search_target() {
+        let cwd = env::current_dir().unwrap();
+        let manifest = cwd.join("Cargo.toml").to_string_lossy().into_owned();
+
+        let targets = search_target("better-config-test", "Cargo.toml", false, true);
+        assert_eq!(targets, [manifest.clone()]);
+
+        let targets = search_target("better-config-test", "Cargo.toml", true, true);
+        assert_eq!(targets.last(), Some(&manifest));
+
+        let targets = search_target("better-config-test", "Cargo.toml", true, false);
+        assert_eq!(targets.first(), Some(&manifest));
+
+        let targets = search_target("better-config-test", "missing.toml?", true, true);
+        assert_eq!(targets, ["missing.toml?"]);
+    }
+
+    #[test]
+    fn test_search_target_searches_each_file() {
+        let cwd = env::current_dir().unwrap();
+        let manifest = cwd.join("Cargo.toml").to_string_lossy().into_owned();
+
+        let targets = search_target(
+            "better-config-test",
+            "missing.toml?, Cargo.toml",
+            false,
+            true,
+        );
+        assert_eq!(targets, ["missing.toml?".to_string(), manifest]);
+    }
+
+    #[test]
+    fn test_search_target_expands_globs() {
+        let cwd = env::current_dir().unwrap();
+        let mut sources: Vec<String> = std::fs::read_dir(cwd.join("src"))
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "rs"))
+            .map(|path| path.to_string_lossy().into_owned())
+            .collect();
+        sources.sort();
+
+        let targets = search_target("better-config-test", "src/*.rs", false, true);
+        assert_eq!(targets, sources);
+
+        // the matches of the most specific directory come last
+        let targets = search_target("better-config-test", "src/*.rs", true, true);
+        assert!(targets.ends_with(&sources));
+    }
+
+    #[test]
+    fn test_search_target_keeps_absolute_paths() {
+        let manifest = env::current_dir().unwrap().join("Cargo.toml");
+        let manifest = manifest.to_string_lossy().into_owned();
+
+        let targets = search_target("better-config-test", &manifest, true, true);
+        assert_eq!(targets, [manifest]);
+    }
+
+    #[test]
+    fn test_load_targets_keeps_commas_in_paths() {
+        let targets = vec!["a,b.toml".to_string(), "c.toml".to_string()];
+        let mut seen = Vec::new();
+        let params = load_targets(targets, |target| {
+            let target = target.unwrap();
+            seen.push(target.clone());
+            Ok(Loaded::from(HashMap::from([("key".to_string(), target)])))
+        })
+        .unwrap();
+        assert_eq!(seen, ["a,b.toml", "c.toml"]);
+        assert_eq!(params.values["key"], "c.toml");
+    }
+}
@@ -0,0 +1,108 @@
+//! Warnings that do not fail loading, e.g. a deprecated key, sent to a pluggable sink
+use crate::location::Location;
+use std::fmt;
+use std::sync::RwLock;
+
+/// A problem with a configuration key that loading tolerates
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Warning {
+    pub key: String,
+    pub message: String,
+    /// Where the key is defined, if the format reports it
+    pub location: Option<Location>,
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(location) = &self.location {
+            write!(f, "{}: ", location)?;
+        }
+        write!(f, "{}", self.message)
+    }
+}
+
+type Sink = Box<dyn Fn(&Warning) + Send + Sync>;
+
+static SINK: RwLock<Option<Sink>> = RwLock::new(None);
+
+/// Send warnings to `sink` instead of the default sink
+///
+/// # Arguments
+///
+/// * `sink` - e.g. [`stderr_sink`], [`log_sink`], [`tracing_sink`] or a closure collecting the
+///   warnings
+pub fn set_warning_sink(sink: impl Fn(&Warning) + Send + Sync + 'static) {
+    *SINK.write().unwrap_or_else(|e| e.into_inner()) = Some(Box::new(sink));
+}
+
+/// Restore the default sink, [`log_sink`] with the `log` feature, otherwise [`stderr_sink`]
+pub fn reset_warning_sink() {
+    *SINK.write().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+/// Report `warning` to the current sink
+pub fn warn(warning: Warning) {
+    match SINK.read().unwrap_or_else(|e| e.into_inner()).as_ref() {
+        Some(sink) => sink(&warning),
+        None => default_sink(&warning),
+    }
+}
+
+#[cfg(feature = "log")]
+fn default_sink(warning: &Warning) {
+    log_sink(warning);
+}
+
+#[cfg(not(feature = "log"))]
+fn default_sink(warning: &Warning) {
+    stderr_sink(warning);
+}
+
+/// A sink printing `warning: ...` to stderr, for applications without a logger
+pub fn stderr_sink(warning: &Warning) {
+    eprintln!("warning: {}", warning);
+}
+
+/// A sink logging warnings with `log::warn!`, enabled with the `log` feature
+#[cfg(feature = "log")]
+pub fn log_sink(warning: &Warning) {
+    log::warn!(target: "better_config", "{}", warning);
+}
+
+/// A sink emitting warnings as `tracing` events, enabled with the `tracing` feature
+#[cfg(feature = "tracing")]
+pub fn tracing_sink(warning: &Warning) {
+    tracing::warn!(target: "better_config", key = %warning.key, "{}", warning);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    #[serial]
+    fn test_custom_sink() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let sink_seen = seen.clone();
+        set_warning_sink(move |w| sink_seen.lock().unwrap().push(w.to_string()));
+
+        warn(Warning {
+            key: "database.host".to_string(),
+            message: "key 'database.host' is deprecated, use 'db.host'".to_string(),
+            location: Some(Location::new("config.toml", 2, 8)),
+        });
+        reset_warning_sink();
+        warn(Warning {
+            key: "ignored".to_string(),
+            message: "goes to the default sink".to_string(),
+            location: None,
+        });
+
+        assert_eq!(
+            *seen.lock().unwrap(),
+            ["config.toml:2:8: key 'database.host' is deprecated, use 'db.host'"]
+        );
+    }
+}
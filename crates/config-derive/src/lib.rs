@@ -1,3 +1,4 @@
+use better_config_core::Format;
 use proc_macro::TokenStream;
 use quote::quote;
 use syn::{
@@ -8,9 +9,24 @@ struct StructEnvArgs {
     trait_path: Option<Path>,
     prefix: Option<String>,
     target: Option<String>,
+    target_env: Option<String>,
+    search: Option<String>,
+    layered: bool,
+    embedded_defaults: Option<String>,
+    sandbox_root: Option<String>,
+    unknown_keys: UnknownKeys,
+    loader_options: Vec<(syn::Ident, syn::Lit)>,
     generic_args: Vec<syn::GenericArgument>,
 }
 
+/// What `build()` does with loaded keys that no field reads.
+#[derive(Clone, Copy, PartialEq)]
+enum UnknownKeys {
+    Ignore,
+    Warn,
+    Deny,
+}
+
 /**
  * `env` macro for deriving environment variable loading functionality.
  *
@@ -54,10 +70,76 @@ fn parse_field_env_args(field: &Field, meta: &Meta) -> Vec<Meta> {
     }
 }
 
+/// Loaders shipped with better-config, which all support loading from a string.
+const BUILTIN_LOADERS: &[&str] = &[
+    "EnvConfig",
+    "TomlConfig",
+    "JsonConfig",
+    "YmlConfig",
+    "IniConfig",
+    "Json5Config",
+    "RonConfig",
+    "HclConfig",
+    "PropertiesConfig",
+    "XmlConfig",
+    "KdlConfig",
+    "FileConfig",
+];
+
+/// The name of the loader trait, e.g. `JsonConfig`, empty for none.
+fn loader_name(env_args: &StructEnvArgs) -> String {
+    env_args
+        .trait_path
+        .as_ref()
+        .and_then(|path| path.segments.last())
+        .map(|segment| segment.ident.to_string())
+        .unwrap_or_default()
+}
+
+fn is_builtin_loader(trait_path: Option<&Path>) -> bool {
+    trait_path
+        .and_then(|path| path.segments.last())
+        .map(|segment| BUILTIN_LOADERS.contains(&segment.ident.to_string().as_str()))
+        .unwrap_or(false)
+}
+
+/// The type of a loader option, a `key = "value"` string or a bare flag.
+#[derive(Clone, Copy, PartialEq)]
+enum OptionType {
+    Str,
+    Bool,
+}
+
+/// The options of each built-in loader, set as the loader trait constants of the struct.
+fn builtin_loader_options(loader: &str) -> &'static [(&'static str, OptionType)] {
+    match loader {
+        "EnvConfig" => &[
+            ("expand", OptionType::Bool),
+            ("strict", OptionType::Bool),
+            ("override_existing", OptionType::Bool),
+        ],
+        "IniConfig" => &[("git_style", OptionType::Bool)],
+        "XmlConfig" => &[("attr_prefix", OptionType::Str)],
+        _ => &[],
+    }
+}
+
+/// A string option, `key = "value"`.
+fn parse_lit_str(nested_meta: &syn::meta::ParseNestedMeta) -> syn::Result<String> {
+    Ok(nested_meta.value()?.parse::<syn::LitStr>()?.value())
+}
+
 // #[env(EnvConfig(prefix = "APP_", target = ".env"))]
-fn parse_struct_env_args(args: Meta) -> StructEnvArgs {
+fn parse_struct_env_args(args: Meta) -> syn::Result<StructEnvArgs> {
     let mut prefix = None;
     let mut target = None;
+    let mut target_env = None;
+    let mut search = None;
+    let mut layered = false;
+    let mut embedded_defaults = None;
+    let mut sandbox_root = None;
+    let mut unknown_keys = UnknownKeys::Ignore;
+    let mut loader_options = Vec::new();
     let mut generic_args = Vec::new();
     let trait_path;
 
@@ -66,25 +148,95 @@ fn parse_struct_env_args(args: Meta) -> StructEnvArgs {
         Meta::List(meta_list) => {
             trait_path = Some(meta_list.path.clone());
 
-            if let Some(last_segment) = meta_list.path.segments.last() {
+            let loader = meta_list.path.segments.last();
+            if let Some(last_segment) = loader {
                 if let syn::PathArguments::AngleBracketed(angle_bracketed) = &last_segment.arguments
                 {
                     generic_args.extend(angle_bracketed.args.iter().cloned());
                 }
             }
+            let loader = loader
+                .map(|segment| segment.ident.to_string())
+                .unwrap_or_default();
+            let is_builtin = BUILTIN_LOADERS.contains(&loader.as_str());
 
-            let _ = meta_list.parse_nested_meta(|nested_meta| {
+            meta_list.parse_nested_meta(|nested_meta| {
                 if nested_meta.path.is_ident("prefix") {
-                    if let Ok(value) = nested_meta.value()?.parse::<syn::LitStr>() {
-                        prefix = Some(value.value());
-                    }
+                    prefix = Some(parse_lit_str(&nested_meta)?);
                 } else if nested_meta.path.is_ident("target") {
-                    if let Ok(value) = nested_meta.value()?.parse::<syn::LitStr>() {
-                        target = Some(value.value());
+                    target = Some(parse_lit_str(&nested_meta)?);
+                } else if nested_meta.path.is_ident("target_env") {
+                    target_env = Some(parse_lit_str(&nested_meta)?);
+                } else if nested_meta.path.is_ident("search") {
+                    search = Some(parse_lit_str(&nested_meta)?);
+                } else if nested_meta.path.is_ident("layered") {
+                    layered = true;
+                } else if nested_meta.path.is_ident("embedded_defaults") {
+                    embedded_defaults = Some(parse_lit_str(&nested_meta)?);
+                } else if nested_meta.path.is_ident("sandbox_root") {
+                    if !is_builtin {
+                        return Err(nested_meta
+                            .error("`sandbox_root` is only supported by the built-in loaders"));
                     }
+                    sandbox_root = Some(parse_lit_str(&nested_meta)?);
+                } else if nested_meta.path.is_ident("deny_unknown_keys") {
+                    unknown_keys = UnknownKeys::Deny;
+                } else if nested_meta.path.is_ident("warn_unknown_keys") {
+                    unknown_keys = UnknownKeys::Warn;
+                } else if let Some(ident) = nested_meta.path.get_ident() {
+                    // any other option belongs to the loader, e.g. `attr_prefix = "_"` or `strict`
+                    let value = if nested_meta.input.peek(Token![=]) {
+                        nested_meta.value()?.parse::<syn::Lit>()?
+                    } else {
+                        syn::Lit::Bool(syn::LitBool::new(true, ident.span()))
+                    };
+                    let option_type = match &value {
+                        syn::Lit::Str(_) => OptionType::Str,
+                        syn::Lit::Bool(_) => OptionType::Bool,
+                        _ => {
+                            return Err(syn::Error::new_spanned(
+                                &value,
+                                format!("loader option `{}` must be a string or a boolean", ident),
+                            ))
+                        }
+                    };
+                    // custom loaders declare their own constants
+                    if is_builtin {
+                        let supported = builtin_loader_options(&loader);
+                        match supported.iter().find(|(name, _)| ident == name) {
+                            Some((_, expected)) if *expected != option_type => {
+                                let expected = match expected {
+                                    OptionType::Str => "a string",
+                                    OptionType::Bool => "a boolean",
+                                };
+                                return Err(syn::Error::new_spanned(
+                                    &value,
+                                    format!("`{}` of {} must be {}", ident, loader, expected),
+                                ));
+                            }
+                            Some(_) => {}
+                            None => {
+                                let names: Vec<_> = supported
+                                    .iter()
+                                    .map(|(name, _)| format!("`{}`", name))
+                                    .collect();
+                                let hint = match names.is_empty() {
+                                    true => format!("{} has no loader options", loader),
+                                    false => format!("expected one of {}", names.join(", ")),
+                                };
+                                return Err(nested_meta.error(format!(
+                                    "unknown option `{}` for {}, {}",
+                                    ident, loader, hint
+                                )));
+                            }
+                        }
+                    }
+                    loader_options.push((ident.clone(), value));
+                } else {
+                    return Err(nested_meta.error("unsupported env option"));
                 }
                 Ok(())
-            });
+            })?;
         }
         // #[env(EnvConfig)]
         Meta::Path(path) => {
@@ -102,22 +254,43 @@ fn parse_struct_env_args(args: Meta) -> StructEnvArgs {
         ),
     }
 
+    // the embedded defaults are parsed with the default options of their format
+    if let (Some(_), Some((ident, _))) = (&embedded_defaults, loader_options.first()) {
+        return Err(syn::Error::new_spanned(
+            ident,
+            format!(
+                "loader option `{}` cannot be combined with `embedded_defaults`",
+                ident
+            ),
+        ));
+    }
+
     // generic_args > 1  => panic!
     if generic_args.len() > 1 {
         panic!("env macro only supports one generic argument");
     }
 
-    StructEnvArgs {
+    Ok(StructEnvArgs {
         trait_path,
         prefix,
         target,
+        target_env,
+        search,
+        layered,
+        embedded_defaults,
+        sandbox_root,
+        unknown_keys,
+        loader_options,
         generic_args,
-    }
+    })
 }
 #[proc_macro_attribute]
 pub fn env(args: TokenStream, input: TokenStream) -> TokenStream {
     let meta = parse_macro_input!(args as Meta);
-    let env_args = parse_struct_env_args(meta);
+    let env_args = match parse_struct_env_args(meta) {
+        Ok(env_args) => env_args,
+        Err(err) => return err.to_compile_error().into(),
+    };
 
     let input_clone = input.clone();
     let input_ref = parse_macro_input!(input_clone as DeriveInput);
@@ -161,11 +334,6 @@ pub fn env(args: TokenStream, input: TokenStream) -> TokenStream {
         }
     });
 
-    let target = match &env_args.target {
-        Some(t) => quote! { Some(#t.to_string()) },
-        None => quote! { None },
-    };
-
     let params_type = if env_args.generic_args.is_empty() {
         quote! { ::std::collections::HashMap<String, String> }
     } else {
@@ -173,6 +341,76 @@ pub fn env(args: TokenStream, input: TokenStream) -> TokenStream {
         quote! { #generic_arg }
     };
 
+    // #[env(TomlConfig(search = "myapp"))] looks for each file of the target in the standard
+    // locations, the found files are loaded one by one
+    let search = env_args.search.as_ref().map(|app| {
+        if !env_args.generic_args.is_empty() {
+            panic!("`search` needs the default HashMap<String, String> params type");
+        }
+        let file_name = match &env_args.target {
+            Some(t) => quote! { #t },
+            None => quote! { <Self as #trait_path<#params_type>>::DEFAULT_TARGET },
+        };
+        let layered = env_args.layered;
+        quote! {
+            ::better_config::utils::search_target(
+                #app,
+                #file_name,
+                #layered,
+                <Self as #trait_path<#params_type>>::LATER_TARGETS_OVERRIDE,
+            )
+        }
+    });
+
+    let target = match (&search, &env_args.target) {
+        (None, Some(t)) => quote! { Some(#t.to_string()) },
+        _ => quote! { None },
+    };
+
+    // #[env(JsonConfig(target_env = "APP_CONFIG"))] lets the environment pick the target at runtime
+    let target_env = match &env_args.target_env {
+        Some(var) => quote! {
+            ::std::env::var(#var)
+                .ok()
+                .filter(|t| !t.trim().is_empty())
+        },
+        None => quote! { None },
+    };
+    let compile_target = target;
+    let target = quote! { #target_env.or_else(|| #compile_target) };
+
+    // a nested `#[env]` struct takes the runtime target of the enclosing one only when it is
+    // loaded by the same loader and declares no target of its own
+    let loader_name = loader_name(&env_args);
+    let inherits_target = env_args.trait_path.is_some()
+        && env_args.target.is_none()
+        && env_args.target_env.is_none()
+        && env_args.search.is_none();
+    let inherit_target = if inherits_target {
+        quote! {
+            if loader == #loader_name && self._target.is_none() {
+                self._target = Some(target.to_string());
+            }
+        }
+    } else {
+        quote! { let _ = (loader, target); }
+    };
+
+    let abstract_load = match &search {
+        Some(search) => quote! {
+            match target.or_else(|| #target) {
+                Some(target) => <Self as #trait_path<#params_type>>::load(Some(target)),
+                None => ::better_config::utils::load_targets(#search, |target| {
+                    <Self as #trait_path<#params_type>>::load(target).map(::better_config::Loaded::from)
+                })
+                .map(|loaded| loaded.values),
+            }
+        },
+        None => quote! {
+            <Self as #trait_path<#params_type>>::load(target.or_else(|| #target))
+        },
+    };
+
     let params_field = quote! {
         _params: ::std::collections::HashMap<String, String>
     };
@@ -216,26 +454,229 @@ pub fn env(args: TokenStream, input: TokenStream) -> TokenStream {
     // Collect excluded keys for no_env_override
     let excluded_keys = collect_excluded_keys(fields, &env_args);
 
-    // Generate the load call - use load_with_override if there are excluded keys
-    // and the trait supports it (file-based loaders)
-    let load_call = if excluded_keys.is_empty() {
-        quote! {
-            <Self as #trait_path<#params_type>>::load(#target)?
-        }
+    // Generate the load call - the built-in loaders return the keys set to null along with the
+    // values, custom loaders use load_with_override if there are excluded keys
+    let is_builtin = is_builtin_loader(trait_path.as_ref());
+    let (load_call, source_load_call) = if is_builtin {
+        let excluded_keys_tokens = excluded_keys.iter().map(|k| quote! { #k.to_string() });
+        let excluded = quote! {
+            let excluded = ::std::collections::HashSet::<String>::from_iter([#(#excluded_keys_tokens),*]);
+        };
+        (
+            quote! {
+                {
+                    #excluded
+                    <Self as #trait_path<#params_type>>::load_detailed(target, &excluded)?
+                }
+            },
+            quote! {
+                {
+                    #excluded
+                    <Self as #trait_path<#params_type>>::load_from_str_detailed(source, &excluded)?
+                }
+            },
+        )
+    } else if excluded_keys.is_empty() {
+        (
+            quote! {
+                ::better_config::Loaded::from(<Self as #trait_path<#params_type>>::load(target)?)
+            },
+            quote! {},
+        )
     } else {
         let excluded_keys_tokens: Vec<_> = excluded_keys
             .iter()
             .map(|k| quote! { #k.to_string() })
             .collect();
+        let excluded = quote! {
+            let mut excluded = ::std::collections::HashSet::new();
+            #(excluded.insert(#excluded_keys_tokens);)*
+        };
+        (
+            quote! {
+                ::better_config::Loaded::from({
+                    #excluded
+                    <Self as #trait_path<#params_type>>::load_with_override(target, &excluded)?
+                })
+            },
+            quote! {},
+        )
+    };
+
+    let load_call = match &search {
+        Some(search) => quote! {
+            match target {
+                Some(target) => {
+                    let target = Some(target);
+                    #load_call
+                }
+                None => ::better_config::utils::load_targets(#search, |target| {
+                    let loaded = #load_call;
+                    Ok(loaded)
+                })?,
+            }
+        },
+        None => load_call,
+    };
+
+    // Only the built-in loaders can read from a string, custom loaders keep the file target
+    let (source_field, source_new_field, source_method, load_call) = if is_builtin {
+        (
+            quote! { _source: Option<String>, },
+            quote! { _source: None, },
+            quote! {
+                /// Load from in-memory content instead of the target, e.g. an embedded
+                /// default or a document fetched over the network.
+                pub fn source_str(&mut self, content: impl Into<String>) -> &mut Self {
+                    self._source = Some(content.into());
+                    self
+                }
+            },
+            quote! {
+                match &self._source {
+                    Some(source) => #source_load_call,
+                    None => #load_call,
+                }
+            },
+        )
+    } else {
+        (quote! {}, quote! {}, quote! {}, load_call)
+    };
+
+    // #[env(TomlConfig(embedded_defaults = "defaults.toml"))] layers checked defaults beneath the
+    // loaded values, whether they come from the target or from `source_str`
+    let load_call = match &env_args.embedded_defaults {
+        Some(file) => {
+            let defaults = handle_embedded_defaults(file, fields, &env_args);
+            let excluded_keys_tokens = excluded_keys.iter().map(|k| quote! { #k.to_string() });
+            quote! {
+                {
+                    let excluded = ::std::collections::HashSet::<String>::from_iter([#(#excluded_keys_tokens),*]);
+                    let mut loaded = ::better_config::merge_loaded_with_env(#defaults, None, &excluded);
+                    loaded.merge(#load_call);
+                    loaded
+                }
+            }
+        }
+        None => load_call,
+    };
+
+    // #[env(XmlConfig(attr_prefix = "_"))] sets `XmlConfig::ATTR_PREFIX` for this struct
+    let mut loader_consts: Vec<_> = env_args
+        .loader_options
+        .iter()
+        .map(|(name, value)| {
+            let const_name = quote::format_ident!("{}", name.to_string().to_uppercase());
+            let const_type = match value {
+                syn::Lit::Str(_) => quote! { &'static str },
+                _ => quote! { bool },
+            };
+            quote! {
+                const #const_name: #const_type = #value;
+            }
+        })
+        .collect();
+
+    // #[env(TomlConfig(sandbox_root = "/etc/app"))] restricts the files of this struct only
+    if let Some(root) = &env_args.sandbox_root {
+        loader_consts.push(quote! {
+            const SANDBOX_ROOT: Option<&'static str> = Some(#root);
+        });
+    }
+
+    // every key the struct reads, nested `#[env]` structs add their own keys
+    let known_keys = fields.iter().map(|field| {
+        if field.attrs.iter().any(|attr| attr.path().is_ident("env")) {
+            let field_type = &field.ty;
+            quote! { keys.extend(<#field_type>::known_keys()); }
+        } else if !has_conf_list(field) {
+            let var = plain_field_var(field);
+            quote! { keys.push(#var.to_string()); }
+        } else {
+            let key = field_key(field, &env_args);
+            let aliases = get_aliases(field);
+            quote! {
+                keys.push(#key.to_string());
+                #(keys.push(#aliases.to_string());)*
+            }
+        }
+    });
+
+    // what each key is, for `json_schema()`, nested `#[env]` structs add their own fields
+    let config_fields = fields.iter().map(|field| {
+        if field.attrs.iter().any(|attr| attr.path().is_ident("env")) {
+            let field_type = &field.ty;
+            quote! { fields.extend(<#field_type>::config_fields()); }
+        } else if !has_conf_list(field) {
+            quote! {}
+        } else {
+            let config_field = config_field(field, &env_args);
+            quote! { fields.push(#config_field); }
+        }
+    });
+
+    // #[conf(min = "1", max = "65535", one_of("a", "b"))] checks the loaded value
+    let constraint_checks = fields.iter().filter_map(|field| {
+        if field.attrs.iter().any(|attr| attr.path().is_ident("env")) {
+            return None;
+        }
+        let key = field_key(field, &env_args);
+        let constraints = get_constraints(field)?;
+        Some(quote! {
+            if let Some(v) = #loaded_params_var.get(#key) {
+                #constraints.check(#key, v, &locations)?;
+            }
+        })
+    });
+
+    let struct_title = struct_name.to_string();
+    let struct_doc = match get_doc(&input_ref.attrs) {
+        Some(doc) => quote! { Some(#doc) },
+        None => quote! { None },
+    };
+
+    // variables set any key of an `EnvConfig` struct, not only the loaded ones
+    let from_env = loader_name == "EnvConfig";
+
+    // `json_schema()` and `sample()` need serde_json, enabled with the `schema` feature
+    let schema_methods = if cfg!(feature = "schema") {
         quote! {
-            {
-                let mut excluded = ::std::collections::HashSet::new();
-                #(excluded.insert(#excluded_keys_tokens);)*
-                <Self as #trait_path<#params_type>>::load_with_override(#target, &excluded)?
+            /// A JSON Schema of the configuration, with types, defaults, constraints and doc comments.
+            pub fn json_schema() -> ::better_config::serde_json::Value {
+                ::better_config::utils::schema::json_schema(#struct_title, #struct_doc, &Self::config_fields())
+            }
+
+            /// A sample configuration file with every key, its default and its doc comment.
+            pub fn sample(format: ::better_config::utils::sample::SampleFormat) -> String {
+                ::better_config::utils::sample::render_sample(&Self::config_fields(), format)
             }
         }
+    } else {
+        quote! {}
     };
 
+    // #[conf(from = "db.host", aliases("database.host"), deprecated = "...")] reports old names
+    let key_migrations = fields.iter().map(|field| {
+        let key = field_key(field, &env_args);
+        let aliases = get_aliases(field);
+        let resolve = (!aliases.is_empty()).then(|| {
+            quote! {
+                ::better_config::utils::keys::resolve_aliases(
+                    &mut #loaded_params_var, #key, &[#(#aliases),*], &mut locations,
+                )?;
+            }
+        });
+        let deprecated = get_var_name(field, "deprecated").map(|note| {
+            quote! {
+                ::better_config::utils::keys::warn_deprecated(&#loaded_params_var, #key, #note, &locations);
+            }
+        });
+        quote! { #resolve #deprecated }
+    });
+
+    // #[env(TomlConfig(deny_unknown_keys))] rejects keys under the prefix that no field reads
+    let unknown_keys_check = handle_unknown_keys(struct_name, &env_args, &loaded_params_var);
+
     let expanded = quote! {
         #(#existing_derives)*
         #vis struct #struct_name {
@@ -248,10 +689,33 @@ pub fn env(args: TokenStream, input: TokenStream) -> TokenStream {
             pub fn builder() -> #struct_builder {
                 #struct_builder::new()
             }
+
+            /// The keys the fields are read from, with the prefix, including nested structs.
+            pub fn known_keys() -> Vec<String> {
+                let mut keys = Vec::new();
+                #(#known_keys)*
+                keys
+            }
+
+            /// What each key is, with the prefix, including nested structs.
+            pub fn config_fields() -> Vec<::better_config::utils::fields::ConfigField> {
+                let mut fields = Vec::new();
+                #(#config_fields)*
+                fields
+            }
+
+            #schema_methods
+
+            /// A Markdown table of the environment variables overriding the keys.
+            pub fn env_docs() -> String {
+                ::better_config::utils::docs::render_env_docs(&Self::config_fields(), #from_env)
+            }
         }
 
         #vis struct #struct_builder {
             #params_field,
+            _target: Option<String>,
+            #source_field
             #(#builder_field_defs),*,
         }
 
@@ -260,13 +724,48 @@ pub fn env(args: TokenStream, input: TokenStream) -> TokenStream {
             pub fn new() -> Self {
                 Self {
                     #params_new_field,
+                    _target: None,
+                    #source_new_field
                     #(#builder_field_assigns),*,
                 }
             }
             // builder methods
+            /// Override the target chosen at compile time, e.g. with a path from the command line.
+            pub fn target(&mut self, target: impl Into<String>) -> &mut Self {
+                self._target = Some(target.into());
+                self
+            }
+
+            /// Take the runtime target of an enclosing config loaded by `loader`.
+            #[doc(hidden)]
+            pub fn inherit_target(&mut self, loader: &str, target: &str) -> &mut Self {
+                #inherit_target
+                self
+            }
+
+            #source_method
+
             pub fn build(&mut self) -> Result<#struct_name, better_config::Error> {
-                // load first (with excluded keys if any)
-                let loaded_params = #load_call;
+                // runtime target > target_env > compile-time target > loader default,
+                // nested `#[env]` structs of the same loader without a target of their own are
+                // built with the same runtime target
+                #[allow(unused_variables)]
+                let target_override = self._target.clone().or_else(|| #target_env);
+                let target = target_override.clone().or_else(|| #compile_target);
+                // load first (with excluded keys if any), in-memory content replaces the target,
+                // the keys explicitly set to null are not in the loaded map, the locations of the
+                // loaded keys are kept for the errors of values that do not parse
+                #[allow(unused_variables, unused_mut)]
+                let ::better_config::Loaded {
+                    values: loaded_params,
+                    mut locations,
+                    nulls: null_keys,
+                } = #load_call;
+                #unknown_keys_check
+                #[allow(unused_mut)]
+                let mut #loaded_params_var = #loaded_params_var;
+                #(#key_migrations)*
+                #(#constraint_checks)*
                 let config = #struct_name {
                     _params: loaded_params.clone(),
                     #(#field_assigns),*,
@@ -283,20 +782,24 @@ pub fn env(args: TokenStream, input: TokenStream) -> TokenStream {
         // First implement AbstractConfig
         impl better_config::AbstractConfig<#params_type> for #struct_name {
             fn load(target: Option<String>) -> Result<#params_type, better_config::Error> {
-                // Default to calling EnvConfig's load
-                <Self as #trait_path<#params_type>>::load(#target)
+                // Default to calling EnvConfig's load, the given target wins over the configured one
+                #abstract_load
             }
         }
 
         impl better_config::AbstractConfig<#params_type> for #struct_builder {
             fn load(target: Option<String>) -> Result<#params_type, better_config::Error> {
-                // Default to calling EnvConfig's load
-                <Self as #trait_path<#params_type>>::load(#target)
+                // Default to calling EnvConfig's load, the given target wins over the configured one
+                #abstract_load
             }
         }
 
-        impl #trait_path<#params_type> for #struct_name  {}
-        impl #trait_path<#params_type> for #struct_builder  {}
+        impl #trait_path<#params_type> for #struct_name  {
+            #(#loader_consts)*
+        }
+        impl #trait_path<#params_type> for #struct_builder  {
+            #(#loader_consts)*
+        }
 
     };
 
@@ -320,8 +823,8 @@ fn handle_builder_field_assign(
 
     let from = get_var_name(field, "from");
     let default = get_var_name(field, "default");
-    // if from and default are both None, return None
-    if from.is_none() && default.is_none() {
+    // if from and default are both None, return None, `Option<T>` fields are only set by build
+    if (from.is_none() && default.is_none()) || option_inner_type(field_type).is_some() {
         return quote! {
             #field_name: None
         };
@@ -360,10 +863,15 @@ fn handle_field_assign(
 
     if is_nested {
         let field_type = &field.ty;
+        let loader_name = loader_name(env_args);
         return quote! {
-            #field_name: #field_type::builder()
-                .build()
-                .expect("Failed to build nested config")
+            #field_name: {
+                let mut builder = #field_type::builder();
+                if let Some(target) = &target_override {
+                    builder.inherit_target(#loader_name, target);
+                }
+                builder.build()?
+            }
         };
     }
 
@@ -376,9 +884,9 @@ fn handle_field_assign(
             ),
         }
     } else {
-        let field_name_str = field_name.as_ref().unwrap().to_string().to_uppercase();
+        let field_name_str = plain_field_var(field);
         quote! {
-            #field_name: ::better_config::utils::env::get_or_else(#field_name_str, || panic!("Failed to load from var: {}", #field_name_str))?
+            #field_name: ::better_config::utils::env::get_or_else(#field_name_str, || panic!("Failed to load from var: {}", #field_name_str))
         }
     };
 
@@ -387,6 +895,123 @@ fn handle_field_assign(
     }
 }
 
+/// Reads the embedded defaults file at compile time and checks that it parses and has a key
+/// for every required field, i.e. a non-`Option` `#[conf]` field without `default` or `getter`.
+///
+/// # Returns
+/// * The expression evaluating to the defaults map, the file is embedded with `include_str!`
+///   so that changing it triggers a rebuild.
+fn handle_embedded_defaults(
+    file: &str,
+    fields: &Fields,
+    env_args: &StructEnvArgs,
+) -> proc_macro2::TokenStream {
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap_or_else(|_| ".".to_string());
+    let path = std::path::Path::new(&manifest_dir).join(file);
+    let content = std::fs::read_to_string(&path).unwrap_or_else(|err| {
+        panic!(
+            "Failed to read embedded defaults `{}`: {}",
+            path.display(),
+            err
+        )
+    });
+
+    let format = Format::from_path(file)
+        .or_else(|| loader_format(env_args.trait_path.as_ref()))
+        .unwrap_or_else(|| panic!("Unknown format of embedded defaults `{}`", file));
+    let defaults = better_config_loader::parse_str(&content, format)
+        .unwrap_or_else(|err| panic!("Malformed embedded defaults `{}`: {}", file, err));
+
+    for field in fields {
+        if let Some(key) = required_key(field, env_args) {
+            if !defaults.values.contains_key(&key) {
+                panic!(
+                    "Embedded defaults `{}` have no key `{}` for required field `{}`",
+                    file,
+                    key,
+                    field.ident.as_ref().unwrap()
+                );
+            }
+        }
+    }
+
+    let path = path.to_string_lossy().into_owned();
+    let format = match format {
+        Format::Toml => quote! { ::better_config::Format::Toml },
+        Format::Json => quote! { ::better_config::Format::Json },
+        Format::Json5 => quote! { ::better_config::Format::Json5 },
+        Format::Yml => quote! { ::better_config::Format::Yml },
+        Format::Ini => quote! { ::better_config::Format::Ini },
+        Format::Env => quote! { ::better_config::Format::Env },
+        Format::Kdl => quote! { ::better_config::Format::Kdl },
+        Format::Xml => quote! { ::better_config::Format::Xml },
+        Format::Properties => quote! { ::better_config::Format::Properties },
+        Format::Hcl => quote! { ::better_config::Format::Hcl },
+        Format::Ron => quote! { ::better_config::Format::Ron },
+    };
+    quote! {
+        ::better_config::parse_str(include_str!(#path), #format)
+            .expect("embedded defaults are checked at compile time")
+    }
+}
+
+/// The format read by a built-in loader, `None` for custom loaders.
+fn loader_format(trait_path: Option<&Path>) -> Option<Format> {
+    let segment = trait_path?.segments.last()?;
+    match segment.ident.to_string().as_str() {
+        "EnvConfig" => Some(Format::Env),
+        "TomlConfig" => Some(Format::Toml),
+        "JsonConfig" => Some(Format::Json),
+        "YmlConfig" => Some(Format::Yml),
+        "IniConfig" => Some(Format::Ini),
+        "Json5Config" => Some(Format::Json5),
+        "KdlConfig" => Some(Format::Kdl),
+        "XmlConfig" => Some(Format::Xml),
+        "PropertiesConfig" => Some(Format::Properties),
+        "HclConfig" => Some(Format::Hcl),
+        "RonConfig" => Some(Format::Ron),
+        _ => None,
+    }
+}
+
+/// The key a field is read from, `from` or the uppercased field name, with the struct prefix.
+fn field_key(field: &Field, env_args: &StructEnvArgs) -> String {
+    let key = get_var_name(field, "from")
+        .unwrap_or_else(|| field.ident.as_ref().unwrap().to_string().to_uppercase());
+
+    match env_args.prefix {
+        Some(ref prefix) => format!("{}{}", prefix, key),
+        None => key,
+    }
+}
+
+/// Whether the field has a `#[conf(...)]` attribute, a field without one reads a process env var.
+fn has_conf_list(field: &Field) -> bool {
+    field
+        .attrs
+        .iter()
+        .any(|attr| attr.path().is_ident("conf") && matches!(attr.meta, Meta::List(_)))
+}
+
+/// The process env var a field without `#[conf]` reads, its name in upper case.
+fn plain_field_var(field: &Field) -> String {
+    field.ident.as_ref().unwrap().to_string().to_uppercase()
+}
+
+/// The key of a `#[conf]` field that has neither a `default` nor a `getter` and is not an `Option`.
+fn required_key(field: &Field, env_args: &StructEnvArgs) -> Option<String> {
+    let is_nested = field.attrs.iter().any(|attr| attr.path().is_ident("env"));
+    if is_nested
+        || !has_conf_list(field)
+        || option_inner_type(&field.ty).is_some()
+        || get_var_name(field, "default").is_some()
+        || get_var_name(field, "getter").is_some()
+    {
+        return None;
+    }
+    Some(field_key(field, env_args))
+}
+
 fn handle_field_meta_list(
     env_args: &StructEnvArgs,
     field: &Field,
@@ -395,19 +1020,19 @@ fn handle_field_meta_list(
     let field_name = &field.ident;
     let field_type = &field.ty;
 
-    let mut var_name = get_var_name(field, "from")
-        .unwrap_or_else(|| field_name.as_ref().unwrap().to_string().to_uppercase());
-
-    // Add prefix if specified
-    if let Some(ref prefix) = env_args.prefix {
-        var_name = format!("{}{}", prefix, var_name);
-    }
+    let var_name = field_key(field, env_args);
 
     // handle attributes
     let default = get_var_name(field, "default");
     let setter_name = get_var_name(field, "setter");
     let getter_name = get_var_name(field, "getter");
 
+    // a key explicitly set to null is not loaded, it counts as absent, or as `None` for
+    // `Option<T>` fields
+    let loaded_value = quote! {
+        #loaded_params_var.get(#var_name)
+    };
+
     if let Some(getter) = getter_name {
         let getter_ident = quote::format_ident!("{}", getter);
         quote! {
@@ -417,27 +1042,108 @@ fn handle_field_meta_list(
         let setter_ident = quote::format_ident!("{}", setter);
         quote! {
             #field_name: {
-                let value = #loaded_params_var.get(#var_name).cloned().unwrap_or_default();
+                let value = #loaded_value.cloned().unwrap_or_default();
                 self.#setter_ident(value.clone());
                 value
             }
         }
+    } else if let Some(inner_type) = option_inner_type(field_type) {
+        match default {
+            // absent uses the default, null clears it
+            Some(default) => quote! {
+                #field_name: match #loaded_params_var.get(#var_name) {
+                    None if null_keys.contains(#var_name) => None,
+                    None => Some(#default.parse::<#inner_type>().unwrap()),
+                    Some(v) => Some(::better_config::utils::misc::parse_value::<#inner_type>(#var_name, v, &locations)?),
+                }
+            },
+            None => quote! {
+                #field_name: #loaded_value
+                    .map(|v| ::better_config::utils::misc::parse_value::<#inner_type>(#var_name, v, &locations))
+                    .transpose()?
+            },
+        }
     } else if let Some(default) = default {
         quote! {
-            #field_name: #loaded_params_var.get(#var_name)
-                .and_then(|v| v.parse::<#field_type>().ok())
-                .unwrap_or_else(|| #default.parse::<#field_type>().unwrap())
-
+            #field_name: match #loaded_value {
+                Some(v) => ::better_config::utils::misc::parse_value::<#field_type>(#var_name, v, &locations)?,
+                None => #default.parse::<#field_type>().unwrap(),
+            }
         }
     } else {
         quote! {
-            #field_name: #loaded_params_var.get(#var_name)
-                .and_then(|v| v.parse::<#field_type>().ok())
-                .unwrap_or_default()
+            #field_name: match #loaded_value {
+                Some(v) => ::better_config::utils::misc::parse_value::<#field_type>(#var_name, v, &locations)?,
+                None => Default::default(),
+            }
         }
     }
 }
 
+/// The check of loaded keys against `known_keys()` for `deny_unknown_keys` and `warn_unknown_keys`.
+fn handle_unknown_keys(
+    struct_name: &syn::Ident,
+    env_args: &StructEnvArgs,
+    loaded_params_var: &proc_macro2::Ident,
+) -> proc_macro2::TokenStream {
+    if env_args.unknown_keys == UnknownKeys::Ignore {
+        return quote! {};
+    }
+
+    // every variable of the process would be unknown
+    let is_env_loader = env_args
+        .trait_path
+        .as_ref()
+        .and_then(|path| path.segments.last())
+        .is_some_and(|segment| segment.ident == "EnvConfig");
+    let prefix = match &env_args.prefix {
+        Some(prefix) => quote! { Some(#prefix) },
+        None if is_env_loader => {
+            panic!("`deny_unknown_keys` and `warn_unknown_keys` need a `prefix` with EnvConfig")
+        }
+        None => quote! { None },
+    };
+
+    let report = match env_args.unknown_keys {
+        UnknownKeys::Deny => quote! {
+            if !unknown.is_empty() {
+                return Err(better_config::Error::UnknownKeysError { keys: unknown });
+            }
+        },
+        _ => quote! {
+            ::better_config::utils::keys::warn_unknown_keys(&unknown);
+        },
+    };
+
+    quote! {
+        let unknown = ::better_config::utils::keys::unknown_keys(
+            &#loaded_params_var,
+            &#struct_name::known_keys(),
+            #prefix,
+            &locations,
+        );
+        #report
+    }
+}
+
+/// Returns `T` for a field of type `Option<T>`.
+fn option_inner_type(ty: &syn::Type) -> Option<&syn::Type> {
+    let syn::Type::Path(type_path) = ty else {
+        return None;
+    };
+    let segment = type_path.path.segments.last()?;
+    if segment.ident != "Option" {
+        return None;
+    }
+    match &segment.arguments {
+        syn::PathArguments::AngleBracketed(args) if args.args.len() == 1 => match &args.args[0] {
+            syn::GenericArgument::Type(inner) => Some(inner),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
 fn handle_field_assigns<'a>(
     fields: &'a Fields,
     env_args: &'a StructEnvArgs,
@@ -506,6 +1212,153 @@ fn get_var_name(field: &Field, field_name: &'static str) -> Option<String> {
     None
 }
 
+/// The old names of a field, `#[conf(aliases("database.host", "DB_HOST"))]`, used as written.
+fn get_aliases(field: &Field) -> Vec<String> {
+    get_str_list(field, "aliases")
+}
+
+/// The strings of a list attribute, e.g. `#[conf(one_of("debug", "info"))]`.
+fn get_str_list(field: &Field, list_name: &'static str) -> Vec<String> {
+    let mut values = Vec::new();
+    for attr in &field.attrs {
+        if !attr.path().is_ident("conf") || !matches!(attr.meta, Meta::List(_)) {
+            continue;
+        }
+        let _ = attr.parse_nested_meta(|meta| {
+            if meta.input.peek(syn::token::Paren) {
+                let content;
+                syn::parenthesized!(content in meta.input);
+                let items = Punctuated::<syn::LitStr, Token![,]>::parse_terminated(&content)?;
+                if meta.path.is_ident(list_name) {
+                    values.extend(items.iter().map(|item| item.value()));
+                }
+            } else if meta.input.peek(Token![=]) {
+                meta.value()?.parse::<syn::Expr>()?;
+            }
+            Ok(())
+        });
+    }
+    values
+}
+
+/// The doc comment of an item, each line trimmed, `None` if there is none.
+fn get_doc(attrs: &[syn::Attribute]) -> Option<String> {
+    let lines: Vec<String> = attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("doc"))
+        .filter_map(|attr| match &attr.meta {
+            Meta::NameValue(syn::MetaNameValue {
+                value:
+                    syn::Expr::Lit(syn::ExprLit {
+                        lit: syn::Lit::Str(lit_str),
+                        ..
+                    }),
+                ..
+            }) => Some(lit_str.value().trim().to_string()),
+            _ => None,
+        })
+        .collect();
+    let doc = lines.join("\n").trim().to_string();
+    (!doc.is_empty()).then_some(doc)
+}
+
+/// The `ValueType` a field type parses from, by the name of the type.
+fn value_type(ty: &syn::Type) -> proc_macro2::TokenStream {
+    let ty = option_inner_type(ty).unwrap_or(ty);
+    let name = match ty {
+        syn::Type::Path(type_path) => type_path
+            .path
+            .segments
+            .last()
+            .map(|segment| segment.ident.to_string())
+            .unwrap_or_default(),
+        _ => String::new(),
+    };
+    match name.as_str() {
+        "i8" | "i16" | "i32" | "i64" | "i128" | "isize" | "u8" | "u16" | "u32" | "u64" | "u128"
+        | "usize" => quote! { ::better_config::utils::fields::ValueType::Integer },
+        "f32" | "f64" => quote! { ::better_config::utils::fields::ValueType::Number },
+        "bool" => quote! { ::better_config::utils::fields::ValueType::Boolean },
+        "String" | "char" | "PathBuf" => {
+            quote! { ::better_config::utils::fields::ValueType::String }
+        }
+        _ => quote! { ::better_config::utils::fields::ValueType::Any },
+    }
+}
+
+/// The `Constraints` of `#[conf(min = "1", max = "65535", one_of("a", "b"))]`, `None` without any.
+fn get_constraints(field: &Field) -> Option<proc_macro2::TokenStream> {
+    let bound = |name: &'static str| {
+        get_var_name(field, name).map(|value| {
+            let value = value.trim().parse::<f64>().unwrap_or_else(|_| {
+                panic!(
+                    "`{}` of field `{}` must be a number, got `{}`",
+                    name,
+                    field.ident.as_ref().unwrap(),
+                    value
+                )
+            });
+            proc_macro2::Literal::f64_suffixed(value)
+        })
+    };
+    let min = bound("min");
+    let max = bound("max");
+    let one_of = get_str_list(field, "one_of");
+    if min.is_none() && max.is_none() && one_of.is_empty() {
+        return None;
+    }
+
+    let min = match min {
+        Some(min) => quote! { Some(#min) },
+        None => quote! { None },
+    };
+    let max = match max {
+        Some(max) => quote! { Some(#max) },
+        None => quote! { None },
+    };
+    Some(quote! {
+        ::better_config::utils::fields::Constraints {
+            min: #min,
+            max: #max,
+            one_of: &[#(#one_of),*],
+        }
+    })
+}
+
+/// The `ConfigField` describing a field that is not a nested struct.
+fn config_field(field: &Field, env_args: &StructEnvArgs) -> proc_macro2::TokenStream {
+    let key = field_key(field, env_args);
+    let field_type = option_inner_type(&field.ty).unwrap_or(&field.ty);
+    let type_name = quote!(#field_type).to_string().replace(' ', "");
+    let value_type = value_type(&field.ty);
+    let default = match get_var_name(field, "default") {
+        Some(default) => quote! { Some(#default) },
+        None => quote! { None },
+    };
+    let required = required_key(field, env_args).is_some();
+    let doc = match get_doc(&field.attrs) {
+        Some(doc) => quote! { Some(#doc) },
+        None => quote! { None },
+    };
+    let constraints = get_constraints(field)
+        .unwrap_or_else(|| quote! { ::better_config::utils::fields::Constraints::default() });
+    let secret = has_conf_flag(field, "secret");
+    let env_override = !has_no_env_override(field);
+    quote! {
+        ::better_config::utils::fields::ConfigField {
+            key: #key,
+            type_name: #type_name,
+            value_type: #value_type,
+            default: #default,
+            required: #required,
+            doc: #doc,
+            constraints: #constraints,
+            secret: #secret,
+            env_override: #env_override,
+        }
+    }
+}
+
 /// Checks if a field has the `no_env_override` attribute set.
 ///
 /// # Arguments
@@ -521,6 +1374,11 @@ fn get_var_name(field: &Field, field_name: &'static str) -> Option<String> {
 /// assert!(has_no_override);
 /// ```
 fn has_no_env_override(field: &Field) -> bool {
+    has_conf_flag(field, "no_env_override")
+}
+
+/// Checks if a field has a bare flag such as `#[conf(secret)]`.
+fn has_conf_flag(field: &Field, flag: &'static str) -> bool {
     for attr in &field.attrs {
         if attr.path().is_ident("conf") {
             if let Meta::List(meta_list) = &attr.meta {
@@ -529,9 +1387,9 @@ fn has_no_env_override(field: &Field) -> bool {
                     meta_list.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)
                 {
                     for meta in args {
-                        // Check for #[conf(no_env_override)] - path-style attribute
+                        // Check for #[conf(flag)] - path-style attribute
                         if let Meta::Path(path) = &meta {
-                            if path.is_ident("no_env_override") {
+                            if path.is_ident(flag) {
                                 return true;
                             }
                         }
@@ -1,13 +1,41 @@
-use better_config_core::{AbstractConfig, Error};
-use dotenvy::from_filename;
+use better_config_core::{misc, AbstractConfig, Error, Loaded, Location, PathPolicy};
 use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::fs;
+use std::io::{ErrorKind, Read};
+use std::path::{Path, PathBuf};
 
 /// Indicates that structure can be initialize from environment variables.
 pub trait EnvConfig<T = HashMap<String, String>>: AbstractConfig<T> {
+    /// Target used when none is given.
+    const DEFAULT_TARGET: &'static str = ".env";
+
+    /// Whether files later in the target override earlier ones, env files never
+    /// override variables that are already set unless `OVERRIDE_EXISTING` is enabled.
+    const LATER_TARGETS_OVERRIDE: bool = false;
+
+    /// Only load files that resolve inside this directory, set with
+    /// `#[env(EnvConfig(sandbox_root = "/etc/app"))]`.
+    const SANDBOX_ROOT: Option<&'static str> = None;
+
+    /// Whether `$VAR` and `${VAR}` references in unquoted and double-quoted values are expanded,
+    /// set with `#[env(EnvConfig(expand = false))]`.
+    const EXPAND: bool = true;
+
+    /// Whether lines that are not valid env syntax are rejected with their line number instead
+    /// of being skipped, set with `#[env(EnvConfig(strict))]`.
+    const STRICT: bool = false;
+
+    /// Whether values from env files replace variables that are already set, which also makes
+    /// later files override earlier ones, set with `#[env(EnvConfig(override_existing))]`.
+    const OVERRIDE_EXISTING: bool = false;
+
     /// Load specified env files to environment variables and initialize the structure.
     ///
     /// # Arguments
-    /// * `target` - A comma-separated string of env file paths, e.g., ".env,.env.local".
+    /// * `target` - A comma-separated string of env file paths, e.g., ".env,.env.local?".
+    ///   Paths ending with `?` are optional and skipped when missing, globs and directories
+    ///   are expanded in lexical order.
     ///
     /// # Errors
     /// * `Error::LoadFileError` - If any of the specified env files cannot be loaded.
@@ -24,20 +52,42 @@ pub trait EnvConfig<T = HashMap<String, String>>: AbstractConfig<T> {
     /// Note: For EnvConfig, excluded_keys has no effect since env vars are the source of truth.
     ///
     /// # Arguments
-    /// * `target` - A comma-separated string of env file paths, e.g., ".env,.env.local".
+    /// * `target` - A comma-separated string of env file paths, e.g., ".env,.env.local?".
+    ///   Paths ending with `?` are optional and skipped when missing.
     /// * `_excluded_keys` - Ignored for EnvConfig (env vars are already the override source).
     ///
     /// # Errors
     /// * `Error::LoadFileError` - If any of the specified env files cannot be loaded.
-    fn load_with_override(target: Option<String>, _excluded_keys: &HashSet<String>) -> Result<T, Error>
+    fn load_with_override(
+        target: Option<String>,
+        excluded_keys: &HashSet<String>,
+    ) -> Result<T, Error>
     where
         T: Default,
         HashMap<String, String>: Into<T>,
         Self: Sized,
     {
-        let target = target.or(Some(".env".to_string()));
+        Ok(Self::load_detailed(target, excluded_keys)?.values.into())
+    }
+
+    /// Load specified env files like [`EnvConfig::load_with_override`], returning the
+    /// environment variables as values.
+    ///
+    /// # Errors
+    /// * `Error::LoadFileError` - If any of the specified env files cannot be loaded.
+    fn load_detailed(
+        target: Option<String>,
+        _excluded_keys: &HashSet<String>,
+    ) -> Result<Loaded, Error>
+    where
+        Self: Sized,
+    {
+        let target = target.or(Some(Self::DEFAULT_TARGET.to_string()));
+
+        let mut locations = HashMap::new();
 
         if let Some(target) = target {
+            let policy = PathPolicy::from_root(Self::SANDBOX_ROOT);
             let file_paths: Vec<String> = target
                 .split(',')
                 .map(|s| s.trim().to_string())
@@ -45,20 +95,519 @@ pub trait EnvConfig<T = HashMap<String, String>>: AbstractConfig<T> {
                 .collect();
 
             for file_path in file_paths {
-                if let Err(e) = from_filename(&file_path) {
-                    return Err(Error::LoadFileError {
-                        name: file_path,
-                        source: Some(Box::new(e)),
-                    });
+                let (file_path, optional) = misc::split_optional(&file_path);
+
+                // Globs and directories expand in lexical order, plain paths are kept as is
+                let expanded = misc::expand_target_path(file_path, &[])?;
+                if expanded.is_empty() && !optional {
+                    return Err(Error::no_files_matched(file_path));
+                }
+
+                for file_path in expanded {
+                    policy.check(&file_path)?;
+
+                    // Relative paths are also searched in parent directories, unless sandboxed
+                    let found = match policy.root() {
+                        Some(_) => Some(PathBuf::from(&file_path)),
+                        None => find_in_ancestors(Path::new(&file_path)),
+                    };
+                    let content = match found.map(fs::read_to_string) {
+                        Some(Ok(content)) => content,
+                        Some(Err(e)) if e.kind() != ErrorKind::NotFound => {
+                            return Err(Error::LoadFileError {
+                                name: file_path,
+                                source: Some(Box::new(e)),
+                            });
+                        }
+                        _ if optional => continue,
+                        _ => {
+                            return Err(Error::LoadFileError {
+                                name: file_path,
+                                source: Some(Box::new(std::io::Error::from(ErrorKind::NotFound))),
+                            });
+                        }
+                    };
+
+                    apply(&content, &file_path, options::<T, Self>(), &mut locations)?;
                 }
             }
         }
 
-        let mut env_map = HashMap::new();
-        for (key, value) in std::env::vars() {
-            env_map.insert(key, value);
+        Ok(Loaded {
+            values: env_map(),
+            locations,
+            ..Loaded::default()
+        })
+    }
+
+    /// Load env content from a string to environment variables and initialize the structure.
+    /// Variables that are already set are kept, like when loading an env file.
+    ///
+    /// # Arguments
+    /// * `content` - Env content, e.g. embedded with `include_str!`.
+    ///
+    /// # Errors
+    /// * `Error::ParseError` - If the content is not a valid env file.
+    fn load_from_str(content: &str) -> Result<T, Error>
+    where
+        T: Default,
+        HashMap<String, String>: Into<T>,
+        Self: Sized,
+    {
+        Self::load_from_str_with_override(content, &HashSet::new())
+    }
+
+    /// Load env content from a string, see [`EnvConfig::load_with_override`].
+    ///
+    /// # Arguments
+    /// * `content` - Env content.
+    /// * `_excluded_keys` - Ignored for EnvConfig (env vars are already the override source).
+    ///
+    /// # Errors
+    /// * `Error::ParseError` - If the content is not a valid env file.
+    fn load_from_str_with_override(
+        content: &str,
+        excluded_keys: &HashSet<String>,
+    ) -> Result<T, Error>
+    where
+        T: Default,
+        HashMap<String, String>: Into<T>,
+        Self: Sized,
+    {
+        Ok(Self::load_from_str_detailed(content, excluded_keys)?
+            .values
+            .into())
+    }
+
+    /// Load env content from a string like [`EnvConfig::load_from_str_with_override`],
+    /// returning the environment variables as values.
+    ///
+    /// # Errors
+    /// * `Error::ParseError` - If the content is not a valid env file.
+    fn load_from_str_detailed(
+        content: &str,
+        _excluded_keys: &HashSet<String>,
+    ) -> Result<Loaded, Error>
+    where
+        Self: Sized,
+    {
+        let mut locations = HashMap::new();
+        apply(
+            content,
+            misc::INLINE_SOURCE,
+            options::<T, Self>(),
+            &mut locations,
+        )?;
+
+        Ok(Loaded {
+            values: env_map(),
+            locations,
+            ..Loaded::default()
+        })
+    }
+
+    /// Load env content from a reader, e.g. stdin, and initialize the structure.
+    ///
+    /// # Errors
+    /// * `Error::IoError` - If the reader fails or yields invalid UTF-8.
+    /// * `Error::ParseError` - If the content is not a valid env file.
+    fn load_from_reader<R: Read>(reader: R) -> Result<T, Error>
+    where
+        T: Default,
+        HashMap<String, String>: Into<T>,
+        Self: Sized,
+    {
+        Self::load_from_str(&misc::read_source(reader)?)
+    }
+}
+
+/// Parsing options taken from the constants of `C`.
+fn options<T, C: EnvConfig<T>>() -> EnvOptions {
+    EnvOptions {
+        expand: C::EXPAND,
+        strict: C::STRICT,
+        override_existing: C::OVERRIDE_EXISTING,
+    }
+}
+
+fn env_map() -> HashMap<String, String> {
+    let mut env_map = HashMap::new();
+    for (key, value) in std::env::vars() {
+        env_map.insert(key, value);
+    }
+    env_map
+}
+
+/// Parse env content named `name` into `map` without touching the environment.
+pub(crate) fn parse_str(content: &str, name: &str, map: &mut Loaded) -> Result<(), Error> {
+    for entry in
+        parse_env(content, EnvOptions::default()).map_err(|e| parse_error(content, name, e))?
+    {
+        let location = Location::in_content(name, content, entry.line, entry.column);
+        map.insert_located(entry.key, entry.value, location);
+    }
+
+    Ok(())
+}
+
+/// Parse env content named `name` and set its variables in the environment, keeping where
+/// each variable that was set is defined in `locations`.
+fn apply(
+    content: &str,
+    name: &str,
+    options: EnvOptions,
+    locations: &mut HashMap<String, Location>,
+) -> Result<(), Error> {
+    for entry in parse_env(content, options).map_err(|e| parse_error(content, name, e))? {
+        if options.override_existing || std::env::var_os(&entry.key).is_none() {
+            let location = Location::in_content(name, content, entry.line, entry.column);
+            locations.insert(entry.key.clone(), location);
+            std::env::set_var(entry.key, entry.value);
         }
+    }
+
+    Ok(())
+}
 
-        Ok(env_map.into())
+fn parse_error(content: &str, name: &str, error: EnvError) -> Error {
+    let location = Location::in_content(name, content, error.line, 1);
+    Error::parse_env_error(name, error).with_location(location)
+}
+
+/// Look for `path` as given, then in each parent of the current directory if it is relative.
+fn find_in_ancestors(path: &Path) -> Option<PathBuf> {
+    if path.is_file() || path.is_absolute() {
+        return Some(path.to_path_buf());
+    }
+
+    let cwd = std::env::current_dir().ok()?;
+    cwd.ancestors()
+        .skip(1)
+        .map(|dir| dir.join(path))
+        .find(|candidate| candidate.is_file())
+}
+
+/// How env content is interpreted, see the constants of [`EnvConfig`].
+#[derive(Debug, Clone, Copy)]
+struct EnvOptions {
+    expand: bool,
+    strict: bool,
+    override_existing: bool,
+}
+
+impl Default for EnvOptions {
+    fn default() -> Self {
+        EnvOptions {
+            expand: true,
+            strict: false,
+            override_existing: false,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct EnvError {
+    line: usize,
+    message: String,
+}
+
+impl fmt::Display for EnvError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for EnvError {}
+
+/// A variable and where its value starts.
+struct EnvEntry {
+    key: String,
+    value: String,
+    line: usize,
+    column: usize,
+}
+
+/// Split env content into key/value pairs.
+///
+/// Lines look like `[export] KEY=value`, values are unquoted (trimmed, `#` after whitespace
+/// starts a comment), single-quoted (literal) or double-quoted (with `\n`, `\t`, `\"`... escapes),
+/// and quoted values may span several lines. Malformed lines are skipped unless `strict` is set.
+fn parse_env(content: &str, options: EnvOptions) -> Result<Vec<EnvEntry>, EnvError> {
+    let mut parser = EnvParser {
+        chars: content.chars().collect(),
+        pos: 0,
+        line: 1,
+        line_start: 0,
+        options,
+        defined: HashMap::new(),
+    };
+    let mut entries = Vec::new();
+
+    loop {
+        parser.skip_while(|c| c.is_whitespace());
+        if parser.peek().is_none() {
+            break;
+        }
+
+        let (start, line, line_start) = (parser.pos, parser.line, parser.line_start);
+        match parser.entry() {
+            Ok(Some(entry)) => {
+                parser
+                    .defined
+                    .insert(entry.key.clone(), entry.value.clone());
+                entries.push(entry);
+            }
+            Ok(None) => {}
+            Err(message) if options.strict => return Err(EnvError { line, message }),
+            Err(_) => {
+                // skip the line where the malformed entry starts
+                parser.pos = start;
+                parser.line = line;
+                parser.line_start = line_start;
+                parser.skip_while(|c| c != '\n');
+            }
+        }
+    }
+
+    Ok(entries)
+}
+
+struct EnvParser {
+    chars: Vec<char>,
+    pos: usize,
+    line: usize,
+    /// Position of the first character of the current line.
+    line_start: usize,
+    options: EnvOptions,
+    /// Values defined earlier in the content, used for expansion.
+    defined: HashMap<String, String>,
+}
+
+impl EnvParser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += 1;
+        if c == '\n' {
+            self.line += 1;
+            self.line_start = self.pos;
+        }
+        Some(c)
+    }
+
+    fn skip_while(&mut self, f: impl Fn(char) -> bool) {
+        while self.peek().is_some_and(&f) {
+            self.next();
+        }
+    }
+
+    fn skip_blanks(&mut self) {
+        self.skip_while(|c| c == ' ' || c == '\t');
+    }
+
+    fn at_line_end(&self) -> bool {
+        matches!(self.peek(), None | Some('\n') | Some('\r'))
+    }
+
+    /// Parse one line, `None` for comments.
+    fn entry(&mut self) -> Result<Option<EnvEntry>, String> {
+        if self.peek() == Some('#') {
+            self.skip_while(|c| c != '\n');
+            return Ok(None);
+        }
+
+        let mut key = self.key()?;
+        if key == "export" && matches!(self.peek(), Some(' ') | Some('\t')) {
+            self.skip_blanks();
+            key = self.key()?;
+        }
+
+        self.skip_blanks();
+        if self.next() != Some('=') {
+            return Err(format!("expected '=' after key '{}'", key));
+        }
+        self.skip_blanks();
+
+        let (line, column) = (self.line, self.pos - self.line_start + 1);
+        let value = match self.peek() {
+            Some('\'') => self.single_quoted()?,
+            Some('"') => self.double_quoted()?,
+            _ => {
+                let value = self.unquoted()?;
+                return Ok(Some(EnvEntry {
+                    key,
+                    value,
+                    line,
+                    column,
+                }));
+            }
+        };
+
+        // only a comment may follow a quoted value
+        self.skip_blanks();
+        if self.peek() == Some('#') {
+            self.skip_while(|c| c != '\n');
+        }
+        if !self.at_line_end() {
+            return Err(format!(
+                "unexpected characters after the value of '{}'",
+                key
+            ));
+        }
+
+        Ok(Some(EnvEntry {
+            key,
+            value,
+            line,
+            column,
+        }))
+    }
+
+    fn key(&mut self) -> Result<String, String> {
+        let start = self.pos;
+        if self
+            .peek()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        {
+            self.skip_while(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.');
+        }
+        if start == self.pos {
+            let found: String = self.chars[start..]
+                .iter()
+                .take_while(|c| **c != '\n')
+                .collect();
+            return Err(format!("invalid key in '{}'", found.trim_end()));
+        }
+
+        Ok(self.chars[start..self.pos].iter().collect())
+    }
+
+    fn single_quoted(&mut self) -> Result<String, String> {
+        self.next();
+        let mut value = String::new();
+        loop {
+            match self.next() {
+                Some('\'') => return Ok(value),
+                Some(c) => value.push(c),
+                None => return Err("unterminated single-quoted value".to_string()),
+            }
+        }
+    }
+
+    fn double_quoted(&mut self) -> Result<String, String> {
+        self.next();
+        let mut value = String::new();
+        loop {
+            match self.next() {
+                Some('"') => return Ok(value),
+                Some('\\') => match self.next() {
+                    Some('n') => value.push('\n'),
+                    Some('r') => value.push('\r'),
+                    Some('t') => value.push('\t'),
+                    Some(c @ ('"' | '\\' | '$')) => value.push(c),
+                    Some(c) => {
+                        value.push('\\');
+                        value.push(c);
+                    }
+                    None => return Err("unterminated double-quoted value".to_string()),
+                },
+                Some('$') if self.options.expand => value.push_str(&self.reference()?),
+                Some(c) => value.push(c),
+                None => return Err("unterminated double-quoted value".to_string()),
+            }
+        }
+    }
+
+    fn unquoted(&mut self) -> Result<String, String> {
+        let mut value = String::new();
+        while !self.at_line_end() {
+            match self.next() {
+                Some('#') if value.is_empty() || value.ends_with([' ', '\t']) => {
+                    self.skip_while(|c| c != '\n');
+                    break;
+                }
+                Some('\\') if self.options.expand && self.peek() == Some('$') => {
+                    self.next();
+                    value.push('$');
+                }
+                Some('$') if self.options.expand => value.push_str(&self.reference()?),
+                Some(c) => value.push(c),
+                None => break,
+            }
+        }
+
+        Ok(value.trim_end().to_string())
+    }
+
+    /// Expand the reference following a `$`: `NAME`, `{NAME}`, `{NAME:-default}` or `{NAME-default}`.
+    /// Anything else leaves the `$` as is.
+    fn reference(&mut self) -> Result<String, String> {
+        let braced = self.peek() == Some('{');
+        if braced {
+            self.next();
+        }
+
+        let start = self.pos;
+        if self
+            .peek()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        {
+            self.skip_while(|c| c.is_ascii_alphanumeric() || c == '_');
+        }
+        let name: String = self.chars[start..self.pos].iter().collect();
+
+        if !braced {
+            return Ok(match name.is_empty() {
+                true => "$".to_string(),
+                false => self.lookup(&name).unwrap_or_default(),
+            });
+        }
+        if name.is_empty() {
+            return Err("invalid variable reference".to_string());
+        }
+
+        let value = self.lookup(&name);
+        let fallback = match self.peek() {
+            Some(':') if self.chars.get(self.pos + 1) == Some(&'-') => {
+                self.pos += 2;
+                Some(value.as_deref().is_none_or(str::is_empty))
+            }
+            Some('-') => {
+                self.pos += 1;
+                Some(value.is_none())
+            }
+            _ => None,
+        };
+
+        let mut default = String::new();
+        if fallback.is_some() {
+            while !matches!(self.peek(), Some('}') | Some('\n') | None) {
+                match self.next() {
+                    Some('$') => default.push_str(&self.reference()?),
+                    Some(c) => default.push(c),
+                    None => break,
+                }
+            }
+        }
+        if self.next() != Some('}') {
+            return Err(format!("unterminated variable reference '${{{}'", name));
+        }
+
+        Ok(match fallback {
+            Some(true) => default,
+            _ => value.unwrap_or_default(),
+        })
+    }
+
+    /// Values from the environment win over earlier lines unless they are overridden by them.
+    fn lookup(&self, name: &str) -> Option<String> {
+        let defined = self.defined.get(name).cloned();
+        match self.options.override_existing {
+            true => defined.or_else(|| std::env::var(name).ok()),
+            false => std::env::var(name).ok().or(defined),
+        }
     }
 }
@@ -0,0 +1,178 @@
+use crate::{detect_format, load_content, load_files, parse_into};
+use better_config_core::{misc, AbstractConfig, Error, Format, Loaded, PathPolicy};
+use std::collections::{HashMap, HashSet};
+use std::io::Read;
+
+/// Extensions of every format a `FileConfig` target may mix.
+const EXTENSIONS: &[&str] = &[
+    "toml",
+    "json",
+    "json5",
+    "jsonc",
+    "yml",
+    "yaml",
+    "ini",
+    "env",
+    "ron",
+    "hcl",
+    "properties",
+    "xml",
+    "kdl",
+];
+
+/// Indicates that structure can be initialized from files of any supported format.
+///
+/// The format of each file is picked from its extension, see [`Format::from_path`], files
+/// without a known extension are detected from their content.
+pub trait FileConfig<T = HashMap<String, String>>: AbstractConfig<T> {
+    /// Target used when none is given.
+    const DEFAULT_TARGET: &'static str = "config.toml";
+
+    /// Whether files later in the target override earlier ones.
+    const LATER_TARGETS_OVERRIDE: bool = true;
+
+    /// Only load files that resolve inside this directory, set with
+    /// `#[env(FileConfig(sandbox_root = "/etc/app"))]`.
+    const SANDBOX_ROOT: Option<&'static str> = None;
+
+    /// Load specified files and initialize the structure.
+    ///
+    /// # Arguments
+    /// * `target` - A comma-separated string of file paths, e.g., "base.toml,override.yaml?".
+    ///   Paths ending with `?` are optional and skipped when missing, globs and directories
+    ///   are expanded in lexical order.
+    ///
+    /// # Errors
+    /// * `Error::LoadFileError` - If any of the specified files cannot be loaded.
+    /// * `Error::ParseError` - If a file cannot be parsed or its format cannot be detected.
+    fn load(target: Option<String>) -> Result<T, Error>
+    where
+        T: Default,
+        HashMap<String, String>: Into<T>,
+        Self: Sized,
+    {
+        Self::load_with_override(target, &HashSet::new())
+    }
+
+    /// Load specified files with explicit control over which keys should not be overridden.
+    ///
+    /// # Arguments
+    /// * `target` - A comma-separated string of file paths, e.g., "base.toml,override.yaml?".
+    /// * `excluded_keys` - Keys that should not be overridden by environment variables.
+    ///
+    /// # Errors
+    /// * `Error::LoadFileError` - If any of the specified files cannot be loaded.
+    /// * `Error::ParseError` - If a file cannot be parsed or its format cannot be detected.
+    fn load_with_override(
+        target: Option<String>,
+        excluded_keys: &HashSet<String>,
+    ) -> Result<T, Error>
+    where
+        T: Default,
+        HashMap<String, String>: Into<T>,
+        Self: Sized,
+    {
+        Ok(Self::load_detailed(target, excluded_keys)?.values.into())
+    }
+
+    /// Load specified files like [`FileConfig::load_with_override`],
+    /// returning the keys set to null along with the values.
+    ///
+    /// # Errors
+    /// * `Error::LoadFileError` - If any of the specified files cannot be loaded.
+    /// * `Error::ParseError` - If a file cannot be parsed or its format cannot be detected.
+    fn load_detailed(
+        target: Option<String>,
+        excluded_keys: &HashSet<String>,
+    ) -> Result<Loaded, Error>
+    where
+        Self: Sized,
+    {
+        load_files(
+            &target.unwrap_or_else(|| Self::DEFAULT_TARGET.to_string()),
+            EXTENSIONS,
+            &PathPolicy::from_root(Self::SANDBOX_ROOT),
+            excluded_keys,
+            parse_detected,
+        )
+    }
+
+    /// Load content of a detected format from a string and initialize the structure.
+    ///
+    /// # Errors
+    /// * `Error::ParseError` - If the format cannot be detected.
+    fn load_from_str(content: &str) -> Result<T, Error>
+    where
+        T: Default,
+        HashMap<String, String>: Into<T>,
+        Self: Sized,
+    {
+        Self::load_from_str_with_override(content, &HashSet::new())
+    }
+
+    /// Load content of a detected format from a string with explicit control over which keys
+    /// should not be overridden.
+    ///
+    /// # Errors
+    /// * `Error::ParseError` - If the format cannot be detected.
+    fn load_from_str_with_override(
+        content: &str,
+        excluded_keys: &HashSet<String>,
+    ) -> Result<T, Error>
+    where
+        T: Default,
+        HashMap<String, String>: Into<T>,
+        Self: Sized,
+    {
+        Ok(Self::load_from_str_detailed(content, excluded_keys)?
+            .values
+            .into())
+    }
+
+    /// Load content from a string like [`FileConfig::load_from_str_with_override`],
+    /// returning the keys set to null along with the values.
+    ///
+    /// # Errors
+    /// * `Error::ParseError` - If the format cannot be detected.
+    fn load_from_str_detailed(
+        content: &str,
+        excluded_keys: &HashSet<String>,
+    ) -> Result<Loaded, Error>
+    where
+        Self: Sized,
+    {
+        load_content(content, excluded_keys, parse_detected)
+    }
+
+    /// Load content of a detected format from a reader, e.g. stdin, and initialize the structure.
+    ///
+    /// # Errors
+    /// * `Error::IoError` - If the reader fails or yields invalid UTF-8.
+    /// * `Error::ParseError` - If the format cannot be detected.
+    fn load_from_reader<R: Read>(reader: R) -> Result<T, Error>
+    where
+        T: Default,
+        HashMap<String, String>: Into<T>,
+        Self: Sized,
+    {
+        Self::load_from_str(&misc::read_source(reader)?)
+    }
+}
+
+/// Parse `content` named `name` in the format of its extension, or the detected one, into `map`.
+fn parse_detected(content: &str, name: &str, map: &mut Loaded) -> Result<(), Error> {
+    let format = match Format::from_path(name) {
+        Some(format) => format,
+        None => sniff(content, name)?,
+    };
+    parse_into(content, format, name, map)
+}
+
+fn sniff(content: &str, name: &str) -> Result<Format, Error> {
+    detect_format(content).ok_or_else(|| Error::ParseError {
+        name: name.to_string(),
+        format: "unknown".to_string(),
+        source: Some("the configuration format cannot be detected".into()),
+        location: None,
+    })
+}
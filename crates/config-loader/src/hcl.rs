@@ -0,0 +1,163 @@
+use crate::json::flatten_json_value;
+use crate::{load_content, load_files};
+use better_config_core::{misc, AbstractConfig, Error, Loaded, Location, PathPolicy};
+use std::collections::{HashMap, HashSet};
+use std::io::Read;
+
+/// Indicates that structure can be initialized from HCL file.
+pub trait HclConfig<T = HashMap<String, String>>: AbstractConfig<T> {
+    /// Target used when none is given.
+    const DEFAULT_TARGET: &'static str = "config.hcl";
+
+    /// Whether files later in the target override earlier ones.
+    const LATER_TARGETS_OVERRIDE: bool = true;
+
+    /// Only load files that resolve inside this directory, set with
+    /// `#[env(HclConfig(sandbox_root = "/etc/app"))]`.
+    const SANDBOX_ROOT: Option<&'static str> = None;
+
+    /// Load specified HCL file and initialize the structure.
+    ///
+    /// # Arguments
+    /// * `target` - Path to the HCL file.
+    ///
+    /// # Errors
+    /// * `Error::LoadFileError` - If the specified HCL file cannot be loaded or parsed.
+    fn load(target: Option<String>) -> Result<T, Error>
+    where
+        T: Default,
+        HashMap<String, String>: Into<T>,
+        Self: Sized,
+    {
+        Self::load_with_override(target, &HashSet::new())
+    }
+
+    /// Load specified HCL file with explicit control over which keys should not be overridden.
+    ///
+    /// # Arguments
+    /// * `target` - Path to the HCL file.
+    /// * `excluded_keys` - Keys that should not be overridden by environment variables.
+    ///
+    /// # Errors
+    /// * `Error::LoadFileError` - If the specified HCL file cannot be loaded or parsed.
+    fn load_with_override(
+        target: Option<String>,
+        excluded_keys: &HashSet<String>,
+    ) -> Result<T, Error>
+    where
+        T: Default,
+        HashMap<String, String>: Into<T>,
+        Self: Sized,
+    {
+        Ok(Self::load_detailed(target, excluded_keys)?.values.into())
+    }
+
+    /// Load specified files like [`HclConfig::load_with_override`],
+    /// returning the keys set to null along with the values.
+    ///
+    /// # Errors
+    /// * `Error::LoadFileError` - If the specified HCL file cannot be loaded or parsed.
+    fn load_detailed(
+        target: Option<String>,
+        excluded_keys: &HashSet<String>,
+    ) -> Result<Loaded, Error>
+    where
+        Self: Sized,
+    {
+        load_files(
+            &target.unwrap_or_else(|| Self::DEFAULT_TARGET.to_string()),
+            &["hcl"],
+            &PathPolicy::from_root(Self::SANDBOX_ROOT),
+            excluded_keys,
+            parse_str,
+        )
+    }
+
+    /// Load HCL content from a string and initialize the structure.
+    ///
+    /// # Arguments
+    /// * `content` - A HCL document, e.g. embedded with `include_str!`.
+    ///
+    /// # Errors
+    /// * `Error::ParseError` - If the content is not valid HCL.
+    fn load_from_str(content: &str) -> Result<T, Error>
+    where
+        T: Default,
+        HashMap<String, String>: Into<T>,
+        Self: Sized,
+    {
+        Self::load_from_str_with_override(content, &HashSet::new())
+    }
+
+    /// Load HCL content from a string with explicit control over which keys should not be overridden.
+    ///
+    /// # Arguments
+    /// * `content` - A HCL document.
+    /// * `excluded_keys` - Keys that should not be overridden by environment variables.
+    ///
+    /// # Errors
+    /// * `Error::ParseError` - If the content is not valid HCL.
+    fn load_from_str_with_override(
+        content: &str,
+        excluded_keys: &HashSet<String>,
+    ) -> Result<T, Error>
+    where
+        T: Default,
+        HashMap<String, String>: Into<T>,
+        Self: Sized,
+    {
+        Ok(Self::load_from_str_detailed(content, excluded_keys)?
+            .values
+            .into())
+    }
+
+    /// Load content from a string like [`HclConfig::load_from_str_with_override`],
+    /// returning the keys set to null along with the values.
+    ///
+    /// # Errors
+    /// * `Error::ParseError` - If the content is not valid HCL.
+    fn load_from_str_detailed(
+        content: &str,
+        excluded_keys: &HashSet<String>,
+    ) -> Result<Loaded, Error>
+    where
+        Self: Sized,
+    {
+        load_content(content, excluded_keys, parse_str)
+    }
+
+    /// Load HCL content from a reader, e.g. stdin, and initialize the structure.
+    ///
+    /// # Errors
+    /// * `Error::IoError` - If the reader fails or yields invalid UTF-8.
+    /// * `Error::ParseError` - If the content is not valid HCL.
+    fn load_from_reader<R: Read>(reader: R) -> Result<T, Error>
+    where
+        T: Default,
+        HashMap<String, String>: Into<T>,
+        Self: Sized,
+    {
+        Self::load_from_str(&misc::read_source(reader)?)
+    }
+}
+
+/// Parse an HCL document named `name` and flatten it into `map`.
+///
+/// Blocks become nested keys, so `upstream "api" { port = 80 }` is flattened to
+/// `upstream.api.port`. Repeated blocks without labels become `block[i]`.
+pub(crate) fn parse_str(content: &str, name: &str, map: &mut Loaded) -> Result<(), Error> {
+    let value: serde_json::Value = hcl::from_str(content).map_err(|e| {
+        let location = match &e {
+            // the parse error renders the offending line itself
+            hcl::Error::Parse(parse) => Some(Location {
+                snippet: None,
+                ..Location::from_offset(name, content, parse.location().offset())
+            }),
+            _ => None,
+        };
+        Error::parse_hcl_error(name, e).with_location(location)
+    })?;
+
+    flatten_json_value(&value, None, map)
+        .map_err(|e| Error::value_conversion_error("hcl", "string", &format!("{}", e)))
+}
@@ -1,9 +1,25 @@
-use better_config_core::{merge_with_env_uppercase, AbstractConfig, Error, misc};
+use crate::{load_content, load_files};
+use better_config_core::{misc, AbstractConfig, Error, Loaded, Location, PathPolicy};
 use ini::Ini;
+use std::borrow::Cow;
 use std::collections::{HashMap, HashSet};
+use std::io::Read;
 
 /// Indicates that structure can be initialized from INI file.
 pub trait IniConfig<T = HashMap<String, String>>: AbstractConfig<T> {
+    /// Target used when none is given.
+    const DEFAULT_TARGET: &'static str = "config.ini";
+
+    /// Whether files later in the target override earlier ones.
+    const LATER_TARGETS_OVERRIDE: bool = true;
+
+    /// Only load files that resolve inside this directory, set with
+    /// `#[env(IniConfig(sandbox_root = "/etc/app"))]`.
+    const SANDBOX_ROOT: Option<&'static str> = None;
+
+    /// Read git-config style files, set with `#[env(IniConfig(git_style))]`.
+    const GIT_STYLE: bool = false;
+
     /// Load specified INI file and initialize the structure.
     ///
     /// # Arguments
@@ -28,45 +44,268 @@ pub trait IniConfig<T = HashMap<String, String>>: AbstractConfig<T> {
     ///
     /// # Errors
     /// * `Error::LoadFileError` - If the specified INI file cannot be loaded or parsed.
-    fn load_with_override(target: Option<String>, excluded_keys: &HashSet<String>) -> Result<T, Error>
+    fn load_with_override(
+        target: Option<String>,
+        excluded_keys: &HashSet<String>,
+    ) -> Result<T, Error>
     where
         T: Default,
         HashMap<String, String>: Into<T>,
         Self: Sized,
     {
-        let target = target.or(Some("config.ini".to_string()));
+        Ok(Self::load_detailed(target, excluded_keys)?.values.into())
+    }
 
-        let mut ini_map = HashMap::new();
+    /// Load specified files like [`IniConfig::load_with_override`],
+    /// returning the keys set to null along with the values.
+    ///
+    /// # Errors
+    /// * `Error::LoadFileError` - If the specified INI file cannot be loaded or parsed.
+    fn load_detailed(
+        target: Option<String>,
+        excluded_keys: &HashSet<String>,
+    ) -> Result<Loaded, Error>
+    where
+        Self: Sized,
+    {
+        load_files(
+            &target.unwrap_or_else(|| Self::DEFAULT_TARGET.to_string()),
+            &["ini"],
+            &PathPolicy::from_root(Self::SANDBOX_ROOT),
+            excluded_keys,
+            |content, name, map| parse_str_with_style(content, name, Self::GIT_STYLE, map),
+        )
+    }
 
-        if let Some(target) = target {
-            let file_paths = misc::validate_and_split_paths(&target)?;
+    /// Load INI content from a string and initialize the structure.
+    ///
+    /// # Arguments
+    /// * `content` - A INI document, e.g. embedded with `include_str!`.
+    ///
+    /// # Errors
+    /// * `Error::ParseError` - If the content is not valid INI.
+    fn load_from_str(content: &str) -> Result<T, Error>
+    where
+        T: Default,
+        HashMap<String, String>: Into<T>,
+        Self: Sized,
+    {
+        Self::load_from_str_with_override(content, &HashSet::new())
+    }
 
-            for file_path in file_paths {
-                // Check file accessibility before reading
-                misc::check_file_accessibility(&file_path)?;
+    /// Load INI content from a string with explicit control over which keys should not be overridden.
+    ///
+    /// # Arguments
+    /// * `content` - A INI document.
+    /// * `excluded_keys` - Keys that should not be overridden by environment variables.
+    ///
+    /// # Errors
+    /// * `Error::ParseError` - If the content is not valid INI.
+    fn load_from_str_with_override(
+        content: &str,
+        excluded_keys: &HashSet<String>,
+    ) -> Result<T, Error>
+    where
+        T: Default,
+        HashMap<String, String>: Into<T>,
+        Self: Sized,
+    {
+        Ok(Self::load_from_str_detailed(content, excluded_keys)?
+            .values
+            .into())
+    }
 
-                let ini = Ini::load_from_file(&file_path)
-                    .map_err(|e| Error::IoError {
-                        operation: format!("load INI file '{}'", file_path),
-                        source: Some(Box::new(e)),
-                    })?;
+    /// Load content from a string like [`IniConfig::load_from_str_with_override`],
+    /// returning the keys set to null along with the values.
+    ///
+    /// # Errors
+    /// * `Error::ParseError` - If the content is not valid INI.
+    fn load_from_str_detailed(
+        content: &str,
+        excluded_keys: &HashSet<String>,
+    ) -> Result<Loaded, Error>
+    where
+        Self: Sized,
+    {
+        load_content(content, excluded_keys, |content, name, map| {
+            parse_str_with_style(content, name, Self::GIT_STYLE, map)
+        })
+    }
+
+    /// Load INI content from a reader, e.g. stdin, and initialize the structure.
+    ///
+    /// # Errors
+    /// * `Error::IoError` - If the reader fails or yields invalid UTF-8.
+    /// * `Error::ParseError` - If the content is not valid INI.
+    fn load_from_reader<R: Read>(reader: R) -> Result<T, Error>
+    where
+        T: Default,
+        HashMap<String, String>: Into<T>,
+        Self: Sized,
+    {
+        Self::load_from_str(&misc::read_source(reader)?)
+    }
+}
+
+/// Section whose keys every other section inherits, like Python's `configparser`
+const DEFAULT_SECTION: &str = "DEFAULT";
+
+/// A key of a section with its values, in the order they are written.
+type Entry<'a> = (String, Vec<&'a str>);
+
+/// Parse an INI document named `name` and flatten it into `map`.
+pub(crate) fn parse_str(content: &str, name: &str, map: &mut Loaded) -> Result<(), Error> {
+    parse_str_with_style(content, name, false, map)
+}
+
+/// Parse an INI document named `name` and flatten it into `map`.
+///
+/// `[a.b]` and `[a "b"]` sections are both keyed `a.b.`, keys repeated within a section,
+/// or in repeated sections, become `key[i]` and the keys of `[DEFAULT]` are inherited by
+/// every other section.
+/// With `git_style`, section and key names are case-insensitive (lowercased, subsections
+/// keep their case), keys without a value are `true` and there is no `[DEFAULT]` section.
+pub(crate) fn parse_str_with_style(
+    content: &str,
+    name: &str,
+    git_style: bool,
+    map: &mut Loaded,
+) -> Result<(), Error> {
+    let content = if git_style {
+        Cow::Owned(bare_keys_as_true(content))
+    } else {
+        Cow::Borrowed(content)
+    };
+    let ini = Ini::load_from_str(&content).map_err(|e| {
+        let location = Location::in_content(name, &content, e.line, e.col);
+        Error::parse_ini_error(name, e).with_location(location)
+    })?;
+
+    // group repeated keys, keeping the order of their first occurrence, repeated sections
+    // add to the keys of the first one
+    let mut sections: Vec<(String, Vec<Entry>)> = Vec::new();
+    for (section, props) in ini.iter() {
+        let section_prefix = match section {
+            Some(s) => format!("{}.", section_key(s, git_style)),
+            None => String::new(),
+        };
+        let entries = match sections.iter().position(|(p, _)| *p == section_prefix) {
+            Some(index) => &mut sections[index].1,
+            None => {
+                sections.push((section_prefix, Vec::new()));
+                &mut sections.last_mut().expect("a section").1
+            }
+        };
+
+        for (key, value) in props.iter() {
+            let key = if git_style {
+                key.to_lowercase()
+            } else {
+                key.to_string()
+            };
+            match entries.iter_mut().find(|(k, _)| *k == key) {
+                Some((_, values)) => values.push(value),
+                None => entries.push((key, vec![value])),
+            }
+        }
+    }
 
-                for (section, props) in ini.iter() {
-                    let section_prefix = match section {
-                        Some(s) => format!("{}.", s),
-                        None => String::new(),
-                    };
+    let default_prefix = format!("{}.", DEFAULT_SECTION);
+    let defaults = match sections.iter().find(|(p, _)| *p == default_prefix) {
+        Some((_, entries)) if !git_style => entries.clone(),
+        _ => Vec::new(),
+    };
 
-                    for (key, value) in props.iter() {
-                        ini_map.insert(format!("{}{}", section_prefix, key), value.to_string());
-                    }
+    for (section_prefix, mut entries) in sections {
+        if !section_prefix.is_empty() && section_prefix != default_prefix {
+            for (key, values) in &defaults {
+                if !entries.iter().any(|(k, _)| k == key) {
+                    entries.push((key.clone(), values.clone()));
                 }
             }
         }
 
-        // Apply environment variable override with excluded keys
-        let ini_map = merge_with_env_uppercase(ini_map, None, excluded_keys);
+        for (key, values) in entries {
+            if let [value] = values.as_slice() {
+                map.insert(format!("{}{}", section_prefix, key), value.to_string());
+            } else {
+                for (i, value) in values.iter().enumerate() {
+                    map.insert(
+                        format!("{}{}[{}]", section_prefix, key, i),
+                        value.to_string(),
+                    );
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Key prefix of a section, `[remote "origin"]` becomes `remote.origin`.
+fn section_key(section: &str, git_style: bool) -> String {
+    let section = section.trim();
+    let (name, subsection) = match section.find('"') {
+        Some(start) => (section[..start].trim(), Some(unquote(&section[start..]))),
+        None => (section, None),
+    };
+
+    let name = if git_style {
+        name.to_lowercase()
+    } else {
+        name.to_string()
+    };
+    match subsection {
+        Some(subsection) => format!("{}.{}", name, subsection),
+        None => name,
+    }
+}
+
+/// Strip the quotes of a subsection name and resolve `\"` and `\\`.
+fn unquote(quoted: &str) -> String {
+    let inner = quoted.strip_prefix('"').unwrap_or(quoted);
+    let inner = inner.strip_suffix('"').unwrap_or(inner);
+
+    let mut unquoted = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => unquoted.extend(chars.next()),
+            c => unquoted.push(c),
+        }
+    }
+    unquoted
+}
+
+/// Give git-config keys without a value, e.g. `bare`, the value `true`.
+fn bare_keys_as_true(content: &str) -> String {
+    content
+        .lines()
+        .map(|line| {
+            let code = strip_comment(line);
+            let trimmed = code.trim();
+            if trimmed.is_empty() || trimmed.starts_with('[') || trimmed.contains('=') {
+                line.to_string()
+            } else {
+                format!("{} = true", code.trim_end())
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
 
-        Ok(ini_map.into())
+/// `line` without its comment, `#` and `;` outside of quotes start one.
+fn strip_comment(line: &str) -> &str {
+    let mut quoted = false;
+    let mut escaped = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '"' => quoted = !quoted,
+            '#' | ';' if !quoted => return &line[..i],
+            _ => {}
+        }
     }
-}
\ No newline at end of file
+    line
+}
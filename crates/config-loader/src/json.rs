@@ -1,9 +1,20 @@
-use better_config_core::{merge_with_env_uppercase, AbstractConfig, Error, misc};
+use crate::{load_content, load_files};
+use better_config_core::{misc, AbstractConfig, Error, Loaded, Location, PathPolicy};
 use std::collections::{HashMap, HashSet};
-use std::fs;
+use std::io::Read;
 
 /// Indicates that structure can be initialized from JSON file.
 pub trait JsonConfig<T = HashMap<String, String>>: AbstractConfig<T> {
+    /// Target used when none is given.
+    const DEFAULT_TARGET: &'static str = "config.json";
+
+    /// Whether files later in the target override earlier ones.
+    const LATER_TARGETS_OVERRIDE: bool = true;
+
+    /// Only load files that resolve inside this directory, set with
+    /// `#[env(JsonConfig(sandbox_root = "/etc/app"))]`.
+    const SANDBOX_ROOT: Option<&'static str> = None;
+
     /// Load specified JSON file and initialize the structure.
     ///
     /// # Arguments
@@ -28,45 +39,125 @@ pub trait JsonConfig<T = HashMap<String, String>>: AbstractConfig<T> {
     ///
     /// # Errors
     /// * `Error::LoadFileError` - If the specified JSON file cannot be loaded or parsed.
-    fn load_with_override(target: Option<String>, excluded_keys: &HashSet<String>) -> Result<T, Error>
+    fn load_with_override(
+        target: Option<String>,
+        excluded_keys: &HashSet<String>,
+    ) -> Result<T, Error>
     where
         T: Default,
         HashMap<String, String>: Into<T>,
         Self: Sized,
     {
-        let target = target.or(Some("config.json".to_string()));
-
-        let mut json_map = HashMap::new();
+        Ok(Self::load_detailed(target, excluded_keys)?.values.into())
+    }
 
-        if let Some(target) = target {
-            let file_paths = misc::validate_and_split_paths(&target)?;
+    /// Load specified files like [`JsonConfig::load_with_override`],
+    /// returning the keys set to null along with the values.
+    ///
+    /// # Errors
+    /// * `Error::LoadFileError` - If the specified JSON file cannot be loaded or parsed.
+    fn load_detailed(
+        target: Option<String>,
+        excluded_keys: &HashSet<String>,
+    ) -> Result<Loaded, Error>
+    where
+        Self: Sized,
+    {
+        load_files(
+            &target.unwrap_or_else(|| Self::DEFAULT_TARGET.to_string()),
+            &["json"],
+            &PathPolicy::from_root(Self::SANDBOX_ROOT),
+            excluded_keys,
+            parse_str,
+        )
+    }
 
-            for file_path in file_paths {
-                // Check file accessibility before reading
-                misc::check_file_accessibility(&file_path)?;
+    /// Load JSON content from a string and initialize the structure.
+    ///
+    /// # Arguments
+    /// * `content` - A JSON document, e.g. embedded with `include_str!`.
+    ///
+    /// # Errors
+    /// * `Error::ParseError` - If the content is not valid JSON.
+    fn load_from_str(content: &str) -> Result<T, Error>
+    where
+        T: Default,
+        HashMap<String, String>: Into<T>,
+        Self: Sized,
+    {
+        Self::load_from_str_with_override(content, &HashSet::new())
+    }
 
-                let content = fs::read_to_string(&file_path)
-                    .map_err(|e| Error::IoError {
-                        operation: format!("read file '{}'", file_path),
-                        source: Some(Box::new(e)),
-                    })?;
+    /// Load JSON content from a string with explicit control over which keys should not be overridden.
+    ///
+    /// # Arguments
+    /// * `content` - A JSON document.
+    /// * `excluded_keys` - Keys that should not be overridden by environment variables.
+    ///
+    /// # Errors
+    /// * `Error::ParseError` - If the content is not valid JSON.
+    fn load_from_str_with_override(
+        content: &str,
+        excluded_keys: &HashSet<String>,
+    ) -> Result<T, Error>
+    where
+        T: Default,
+        HashMap<String, String>: Into<T>,
+        Self: Sized,
+    {
+        Ok(Self::load_from_str_detailed(content, excluded_keys)?
+            .values
+            .into())
+    }
 
-                let value: serde_json::Value = serde_json::from_str(&content)
-                    .map_err(|e| Error::parse_json_error(&file_path, e))?;
+    /// Load content from a string like [`JsonConfig::load_from_str_with_override`],
+    /// returning the keys set to null along with the values.
+    ///
+    /// # Errors
+    /// * `Error::ParseError` - If the content is not valid JSON.
+    fn load_from_str_detailed(
+        content: &str,
+        excluded_keys: &HashSet<String>,
+    ) -> Result<Loaded, Error>
+    where
+        Self: Sized,
+    {
+        load_content(content, excluded_keys, parse_str)
+    }
 
-                flatten_json_value(&value, None, &mut json_map)
-                    .map_err(|e| Error::value_conversion_error("json", "string", &format!("{}", e)))?;
-            }
-        }
+    /// Load JSON content from a reader, e.g. stdin, and initialize the structure.
+    ///
+    /// # Errors
+    /// * `Error::IoError` - If the reader fails or yields invalid UTF-8.
+    /// * `Error::ParseError` - If the content is not valid JSON.
+    fn load_from_reader<R: Read>(reader: R) -> Result<T, Error>
+    where
+        T: Default,
+        HashMap<String, String>: Into<T>,
+        Self: Sized,
+    {
+        Self::load_from_str(&misc::read_source(reader)?)
+    }
+}
 
-        // Apply environment variable override with excluded keys
-        let json_map = merge_with_env_uppercase(json_map, None, excluded_keys);
+/// Parse a JSON document named `name` and flatten it into `map`.
+pub(crate) fn parse_str(content: &str, name: &str, map: &mut Loaded) -> Result<(), Error> {
+    let value: serde_json::Value = serde_json::from_str(content).map_err(|e| {
+        // line 0 means the error is not tied to a position in the input
+        let location =
+            (e.line() > 0).then(|| Location::in_content(name, content, e.line(), e.column()));
+        Error::parse_json_error(name, e).with_location(location)
+    })?;
 
-        Ok(json_map.into())
-    }
+    flatten_json_value(&value, None, map)
+        .map_err(|e| Error::value_conversion_error("json", "string", &format!("{}", e)))
 }
 
-fn flatten_json_value(value: &serde_json::Value, parent_key: Option<String>, map: &mut HashMap<String, String>) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+pub(crate) fn flatten_json_value(
+    value: &serde_json::Value,
+    parent_key: Option<String>,
+    map: &mut Loaded,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     match value {
         serde_json::Value::Object(obj) => {
             for (key, val) in obj {
@@ -112,8 +203,12 @@ fn flatten_json_value(value: &serde_json::Value, parent_key: Option<String>, map
                 map.insert(key, b.to_string());
             }
         }
-        serde_json::Value::Null => {}
+        serde_json::Value::Null => {
+            if let Some(key) = parent_key {
+                map.insert_null(key);
+            }
+        }
     }
 
     Ok(())
-}
\ No newline at end of file
+}
@@ -0,0 +1,231 @@
+use crate::{load_content, load_files, Source};
+use better_config_core::{misc, AbstractConfig, Error, Loaded, Location, PathPolicy};
+use kdl::{KdlDocument, KdlEntry, KdlNode, KdlValue};
+use std::collections::{HashMap, HashSet};
+use std::io::Read;
+
+/// Indicates that structure can be initialized from KDL file.
+pub trait KdlConfig<T = HashMap<String, String>>: AbstractConfig<T> {
+    /// Target used when none is given.
+    const DEFAULT_TARGET: &'static str = "config.kdl";
+
+    /// Whether files later in the target override earlier ones.
+    const LATER_TARGETS_OVERRIDE: bool = true;
+
+    /// Only load files that resolve inside this directory, set with
+    /// `#[env(KdlConfig(sandbox_root = "/etc/app"))]`.
+    const SANDBOX_ROOT: Option<&'static str> = None;
+
+    /// Load specified KDL file and initialize the structure.
+    ///
+    /// # Arguments
+    /// * `target` - Path to the KDL file.
+    ///
+    /// # Errors
+    /// * `Error::LoadFileError` - If the specified KDL file cannot be loaded or parsed.
+    fn load(target: Option<String>) -> Result<T, Error>
+    where
+        T: Default,
+        HashMap<String, String>: Into<T>,
+        Self: Sized,
+    {
+        Self::load_with_override(target, &HashSet::new())
+    }
+
+    /// Load specified KDL file with explicit control over which keys should not be overridden.
+    ///
+    /// # Arguments
+    /// * `target` - Path to the KDL file.
+    /// * `excluded_keys` - Keys that should not be overridden by environment variables.
+    ///
+    /// # Errors
+    /// * `Error::LoadFileError` - If the specified KDL file cannot be loaded or parsed.
+    fn load_with_override(
+        target: Option<String>,
+        excluded_keys: &HashSet<String>,
+    ) -> Result<T, Error>
+    where
+        T: Default,
+        HashMap<String, String>: Into<T>,
+        Self: Sized,
+    {
+        Ok(Self::load_detailed(target, excluded_keys)?.values.into())
+    }
+
+    /// Load specified files like [`KdlConfig::load_with_override`],
+    /// returning the keys set to null along with the values.
+    ///
+    /// # Errors
+    /// * `Error::LoadFileError` - If the specified KDL file cannot be loaded or parsed.
+    fn load_detailed(
+        target: Option<String>,
+        excluded_keys: &HashSet<String>,
+    ) -> Result<Loaded, Error>
+    where
+        Self: Sized,
+    {
+        load_files(
+            &target.unwrap_or_else(|| Self::DEFAULT_TARGET.to_string()),
+            &["kdl"],
+            &PathPolicy::from_root(Self::SANDBOX_ROOT),
+            excluded_keys,
+            parse_str,
+        )
+    }
+
+    /// Load KDL content from a string and initialize the structure.
+    ///
+    /// # Arguments
+    /// * `content` - A KDL document, e.g. embedded with `include_str!`.
+    ///
+    /// # Errors
+    /// * `Error::ParseError` - If the content is not valid KDL.
+    fn load_from_str(content: &str) -> Result<T, Error>
+    where
+        T: Default,
+        HashMap<String, String>: Into<T>,
+        Self: Sized,
+    {
+        Self::load_from_str_with_override(content, &HashSet::new())
+    }
+
+    /// Load KDL content from a string with explicit control over which keys should not be overridden.
+    ///
+    /// # Arguments
+    /// * `content` - A KDL document.
+    /// * `excluded_keys` - Keys that should not be overridden by environment variables.
+    ///
+    /// # Errors
+    /// * `Error::ParseError` - If the content is not valid KDL.
+    fn load_from_str_with_override(
+        content: &str,
+        excluded_keys: &HashSet<String>,
+    ) -> Result<T, Error>
+    where
+        T: Default,
+        HashMap<String, String>: Into<T>,
+        Self: Sized,
+    {
+        Ok(Self::load_from_str_detailed(content, excluded_keys)?
+            .values
+            .into())
+    }
+
+    /// Load content from a string like [`KdlConfig::load_from_str_with_override`],
+    /// returning the keys set to null along with the values.
+    ///
+    /// # Errors
+    /// * `Error::ParseError` - If the content is not valid KDL.
+    fn load_from_str_detailed(
+        content: &str,
+        excluded_keys: &HashSet<String>,
+    ) -> Result<Loaded, Error>
+    where
+        Self: Sized,
+    {
+        load_content(content, excluded_keys, parse_str)
+    }
+
+    /// Load KDL content from a reader, e.g. stdin, and initialize the structure.
+    ///
+    /// # Errors
+    /// * `Error::IoError` - If the reader fails or yields invalid UTF-8.
+    /// * `Error::ParseError` - If the content is not valid KDL.
+    fn load_from_reader<R: Read>(reader: R) -> Result<T, Error>
+    where
+        T: Default,
+        HashMap<String, String>: Into<T>,
+        Self: Sized,
+    {
+        Self::load_from_str(&misc::read_source(reader)?)
+    }
+}
+
+/// Parse a KDL document named `name` and flatten it into `map`.
+///
+/// A node is keyed by its name below its parent node. A single argument is the value of the
+/// node, several arguments and repeated nodes become `key[i]`, properties become `key.prop`.
+pub(crate) fn parse_str(content: &str, name: &str, map: &mut Loaded) -> Result<(), Error> {
+    let document = KdlDocument::parse(content).map_err(|e| {
+        let location = e
+            .diagnostics
+            .first()
+            .map(|diagnostic| Location::from_offset(name, content, diagnostic.span.offset()));
+        Error::parse_kdl_error(name, e).with_location(location)
+    })?;
+
+    let source = Source { content, name };
+    flatten_kdl_document(&document, None, &source, map);
+
+    Ok(())
+}
+
+fn flatten_kdl_document(
+    document: &KdlDocument,
+    parent_key: Option<&str>,
+    source: &Source,
+    map: &mut Loaded,
+) {
+    // group repeated nodes by name, keeping the document order of their first occurrence
+    let mut nodes: Vec<(&str, Vec<&KdlNode>)> = Vec::new();
+    for node in document.nodes() {
+        let name = node.name().value();
+        match nodes.iter_mut().find(|(n, _)| *n == name) {
+            Some((_, group)) => group.push(node),
+            None => nodes.push((name, vec![node])),
+        }
+    }
+
+    for (name, group) in nodes {
+        let key = match parent_key {
+            Some(parent) => format!("{}.{}", parent, name),
+            None => name.to_string(),
+        };
+        if group.len() == 1 {
+            flatten_kdl_node(group[0], &key, source, map);
+        } else {
+            for (i, node) in group.iter().enumerate() {
+                flatten_kdl_node(node, &format!("{}[{}]", key, i), source, map);
+            }
+        }
+    }
+}
+
+fn flatten_kdl_node(node: &KdlNode, key: &str, source: &Source, map: &mut Loaded) {
+    let arguments: Vec<&KdlEntry> = node
+        .entries()
+        .iter()
+        .filter(|entry| entry.name().is_none())
+        .collect();
+
+    match arguments.as_slice() {
+        [] => {}
+        [entry] => insert_kdl_entry(map, key.to_string(), entry, source),
+        entries => {
+            for (i, entry) in entries.iter().enumerate() {
+                insert_kdl_entry(map, format!("{}[{}]", key, i), entry, source);
+            }
+        }
+    }
+
+    for entry in node.entries() {
+        if let Some(name) = entry.name() {
+            insert_kdl_entry(map, format!("{}.{}", key, name.value()), entry, source);
+        }
+    }
+
+    if let Some(children) = node.children() {
+        flatten_kdl_document(children, Some(key), source, map);
+    }
+}
+
+fn insert_kdl_entry(map: &mut Loaded, key: String, entry: &KdlEntry, source: &Source) {
+    let value = match entry.value() {
+        KdlValue::String(s) => s.clone(),
+        KdlValue::Integer(i) => i.to_string(),
+        KdlValue::Float(f) => f.to_string(),
+        KdlValue::Bool(b) => b.to_string(),
+        KdlValue::Null => return map.insert_null(key),
+    };
+    map.insert_located(key, value, source.location(entry.span().offset()));
+}
@@ -12,3 +12,230 @@ config_feature!("json", json, JsonConfig);
 config_feature!("toml", toml, TomlConfig);
 config_feature!("env", env, EnvConfig);
 config_feature!("ini", ini, IniConfig);
+config_feature!("json5", json5, Json5Config);
+config_feature!("ron", ron, RonConfig);
+config_feature!("hcl", hcl, HclConfig);
+config_feature!("properties", properties, PropertiesConfig);
+config_feature!("xml", xml, XmlConfig);
+config_feature!("kdl", kdl, KdlConfig);
+config_feature!("file", file, FileConfig);
+
+use better_config_core::{misc, Error, Format, Loaded};
+
+/// The document being flattened, for the locations of values in formats without spanned values
+#[cfg(any(feature = "xml", feature = "kdl"))]
+pub(crate) struct Source<'a> {
+    pub(crate) content: &'a str,
+    pub(crate) name: &'a str,
+}
+
+#[cfg(any(feature = "xml", feature = "kdl"))]
+impl Source<'_> {
+    /// The location of a value starting at the byte `offset` of the content
+    pub(crate) fn location(&self, offset: usize) -> better_config_core::Location {
+        better_config_core::Location::from_offset(self.name, self.content, offset)
+    }
+}
+
+/// Resolve `target`, parse each of its files with `parse` and apply the environment variable
+/// overrides, except for `excluded_keys`. The body of `load_detailed` of the file loaders.
+///
+/// # Arguments
+/// * `target` - The target, e.g. `config.toml`, a comma-separated list or a glob pattern.
+/// * `extensions` - The extensions of the files loaded from a directory.
+/// * `policy` - Where the files may be loaded from.
+/// * `excluded_keys` - Keys that should not be overridden by environment variables.
+/// * `parse` - Parses the content of a file named by its path into the map.
+///
+/// # Errors
+/// * `Error::IoError` - If a file cannot be read.
+/// * `Error::ParseError` - If `parse` fails.
+#[cfg(any(
+    feature = "toml",
+    feature = "json",
+    feature = "yml",
+    feature = "ini",
+    feature = "json5",
+    feature = "kdl",
+    feature = "xml",
+    feature = "properties",
+    feature = "hcl",
+    feature = "ron",
+    feature = "file"
+))]
+pub(crate) fn load_files(
+    target: &str,
+    extensions: &[&str],
+    policy: &better_config_core::PathPolicy,
+    excluded_keys: &std::collections::HashSet<String>,
+    parse: impl Fn(&str, &str, &mut Loaded) -> Result<(), Error>,
+) -> Result<Loaded, Error> {
+    let mut map = Loaded::new();
+
+    // Expand globs and directories, skip missing optional files
+    for file_path in misc::resolve_target_files_with_policy(target, extensions, policy)? {
+        let content = std::fs::read_to_string(&file_path).map_err(|e| Error::IoError {
+            operation: format!("read file '{}'", file_path),
+            source: Some(Box::new(e)),
+        })?;
+
+        parse(&content, &file_path, &mut map)?;
+    }
+
+    // Apply environment variable override with excluded keys
+    Ok(better_config_core::merge_loaded_with_env(
+        map,
+        None,
+        excluded_keys,
+    ))
+}
+
+/// Parse `content` with `parse` and apply the environment variable overrides, except for
+/// `excluded_keys`. The body of `load_from_str_detailed` of the file loaders.
+///
+/// # Errors
+/// * `Error::ParseError` - If `parse` fails.
+#[cfg(any(
+    feature = "toml",
+    feature = "json",
+    feature = "yml",
+    feature = "ini",
+    feature = "json5",
+    feature = "kdl",
+    feature = "xml",
+    feature = "properties",
+    feature = "hcl",
+    feature = "ron",
+    feature = "file"
+))]
+pub(crate) fn load_content(
+    content: &str,
+    excluded_keys: &std::collections::HashSet<String>,
+    parse: impl Fn(&str, &str, &mut Loaded) -> Result<(), Error>,
+) -> Result<Loaded, Error> {
+    let mut map = Loaded::new();
+    parse(content, misc::INLINE_SOURCE, &mut map)?;
+
+    Ok(better_config_core::merge_loaded_with_env(
+        map,
+        None,
+        excluded_keys,
+    ))
+}
+
+/// Parse configuration content of the given format into the flattened key/value map
+/// the loaders build, without applying environment variable overrides.
+///
+/// Keys set to null, e.g. `port: null` in YAML, are left out of the values and returned in
+/// [`Loaded::nulls`].
+///
+/// # Arguments
+/// * `content` - The configuration content, e.g. read from stdin or fetched over the network.
+/// * `format` - The format of `content`.
+///
+/// # Errors
+/// * `Error::ParseError` - If the content is invalid or support for `format` is not enabled.
+///
+/// # Example
+/// ```rust
+/// use better_config_core::Format;
+///
+/// let loaded = better_config_loader::parse_str("[server]\nport = 8080", Format::Toml).unwrap();
+/// assert_eq!(loaded.values.get("server.port"), Some(&"8080".to_string()));
+/// ```
+pub fn parse_str(content: &str, format: Format) -> Result<Loaded, Error> {
+    let mut map = Loaded::new();
+    parse_into(content, format, misc::INLINE_SOURCE, &mut map)?;
+    Ok(map)
+}
+
+/// Parse `content` named `name` with the parser of `format` and flatten it into `map`.
+#[cfg_attr(
+    not(any(
+        feature = "toml",
+        feature = "json",
+        feature = "yml",
+        feature = "ini",
+        feature = "env",
+        feature = "json5",
+        feature = "kdl",
+        feature = "xml",
+        feature = "properties",
+        feature = "hcl",
+        feature = "ron"
+    )),
+    allow(unused_variables)
+)]
+pub(crate) fn parse_into(
+    content: &str,
+    format: Format,
+    name: &str,
+    map: &mut Loaded,
+) -> Result<(), Error> {
+    match format {
+        #[cfg(feature = "toml")]
+        Format::Toml => self::toml::parse_str(content, name, map),
+        #[cfg(feature = "json")]
+        Format::Json => self::json::parse_str(content, name, map),
+        #[cfg(feature = "json5")]
+        Format::Json5 => self::json5::parse_str(content, name, map),
+        #[cfg(feature = "yml")]
+        Format::Yml => self::yml::parse_str(content, name, map),
+        #[cfg(feature = "ini")]
+        Format::Ini => self::ini::parse_str(content, name, map),
+        #[cfg(feature = "env")]
+        Format::Env => self::env::parse_str(content, name, map),
+        #[cfg(feature = "kdl")]
+        Format::Kdl => self::kdl::parse_str(content, name, map),
+        #[cfg(feature = "xml")]
+        Format::Xml => self::xml::parse_str(content, name, map),
+        #[cfg(feature = "properties")]
+        Format::Properties => self::properties::parse_str(content, name, map),
+        #[cfg(feature = "hcl")]
+        Format::Hcl => self::hcl::parse_str(content, name, map),
+        #[cfg(feature = "ron")]
+        Format::Ron => self::ron::parse_str(content, name, map),
+        #[allow(unreachable_patterns)]
+        _ => Err(Error::unsupported_format(name, format.name())),
+    }
+}
+
+/// Detect the format of configuration content that comes without a file extension.
+///
+/// JSON is tried first, then TOML, YAML (only when it yields a mapping, as any plain
+/// text is a YAML scalar) and finally INI, which also covers `KEY=value` env content.
+/// Only the formats whose features are enabled are considered.
+///
+/// # Example
+/// ```rust
+/// use better_config_core::Format;
+/// use better_config_loader::detect_format;
+///
+/// assert_eq!(detect_format("name: app\nport: 8080"), Some(Format::Yml));
+/// ```
+#[cfg_attr(
+    not(any(feature = "toml", feature = "json", feature = "yml", feature = "ini")),
+    allow(unused_variables)
+)]
+pub fn detect_format(content: &str) -> Option<Format> {
+    #[cfg(feature = "json")]
+    if serde_json::from_str::<serde_json::Value>(content).is_ok() {
+        return Some(Format::Json);
+    }
+    #[cfg(feature = "toml")]
+    if content.parse::<::toml::Table>().is_ok() {
+        return Some(Format::Toml);
+    }
+    #[cfg(feature = "yml")]
+    if matches!(
+        serde_yml::from_str::<serde_yml::Value>(content),
+        Ok(serde_yml::Value::Mapping(_))
+    ) {
+        return Some(Format::Yml);
+    }
+    #[cfg(feature = "ini")]
+    if ::ini::Ini::load_from_str(content).is_ok() {
+        return Some(Format::Ini);
+    }
+    None
+}
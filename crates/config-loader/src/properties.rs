@@ -0,0 +1,284 @@
+use crate::{load_content, load_files};
+use better_config_core::{misc, AbstractConfig, Error, Loaded, Location, PathPolicy};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::io::Read;
+use std::iter::Peekable;
+use std::str::Chars;
+
+/// Indicates that structure can be initialized from Java-style `.properties` file.
+pub trait PropertiesConfig<T = HashMap<String, String>>: AbstractConfig<T> {
+    /// Target used when none is given.
+    const DEFAULT_TARGET: &'static str = "application.properties";
+
+    /// Whether files later in the target override earlier ones.
+    const LATER_TARGETS_OVERRIDE: bool = true;
+
+    /// Only load files that resolve inside this directory, set with
+    /// `#[env(PropertiesConfig(sandbox_root = "/etc/app"))]`.
+    const SANDBOX_ROOT: Option<&'static str> = None;
+
+    /// Load specified PROPERTIES file and initialize the structure.
+    ///
+    /// # Arguments
+    /// * `target` - Path to the PROPERTIES file.
+    ///
+    /// # Errors
+    /// * `Error::LoadFileError` - If the specified PROPERTIES file cannot be loaded or parsed.
+    fn load(target: Option<String>) -> Result<T, Error>
+    where
+        T: Default,
+        HashMap<String, String>: Into<T>,
+        Self: Sized,
+    {
+        Self::load_with_override(target, &HashSet::new())
+    }
+
+    /// Load specified PROPERTIES file with explicit control over which keys should not be overridden.
+    ///
+    /// # Arguments
+    /// * `target` - Path to the PROPERTIES file.
+    /// * `excluded_keys` - Keys that should not be overridden by environment variables.
+    ///
+    /// # Errors
+    /// * `Error::LoadFileError` - If the specified PROPERTIES file cannot be loaded or parsed.
+    fn load_with_override(
+        target: Option<String>,
+        excluded_keys: &HashSet<String>,
+    ) -> Result<T, Error>
+    where
+        T: Default,
+        HashMap<String, String>: Into<T>,
+        Self: Sized,
+    {
+        Ok(Self::load_detailed(target, excluded_keys)?.values.into())
+    }
+
+    /// Load specified files like [`PropertiesConfig::load_with_override`],
+    /// returning the keys set to null along with the values.
+    ///
+    /// # Errors
+    /// * `Error::LoadFileError` - If the specified PROPERTIES file cannot be loaded or parsed.
+    fn load_detailed(
+        target: Option<String>,
+        excluded_keys: &HashSet<String>,
+    ) -> Result<Loaded, Error>
+    where
+        Self: Sized,
+    {
+        load_files(
+            &target.unwrap_or_else(|| Self::DEFAULT_TARGET.to_string()),
+            &["properties"],
+            &PathPolicy::from_root(Self::SANDBOX_ROOT),
+            excluded_keys,
+            parse_str,
+        )
+    }
+
+    /// Load PROPERTIES content from a string and initialize the structure.
+    ///
+    /// # Arguments
+    /// * `content` - A PROPERTIES document, e.g. embedded with `include_str!`.
+    ///
+    /// # Errors
+    /// * `Error::ParseError` - If the content is not valid PROPERTIES.
+    fn load_from_str(content: &str) -> Result<T, Error>
+    where
+        T: Default,
+        HashMap<String, String>: Into<T>,
+        Self: Sized,
+    {
+        Self::load_from_str_with_override(content, &HashSet::new())
+    }
+
+    /// Load PROPERTIES content from a string with explicit control over which keys should not be overridden.
+    ///
+    /// # Arguments
+    /// * `content` - A PROPERTIES document.
+    /// * `excluded_keys` - Keys that should not be overridden by environment variables.
+    ///
+    /// # Errors
+    /// * `Error::ParseError` - If the content is not valid PROPERTIES.
+    fn load_from_str_with_override(
+        content: &str,
+        excluded_keys: &HashSet<String>,
+    ) -> Result<T, Error>
+    where
+        T: Default,
+        HashMap<String, String>: Into<T>,
+        Self: Sized,
+    {
+        Ok(Self::load_from_str_detailed(content, excluded_keys)?
+            .values
+            .into())
+    }
+
+    /// Load content from a string like [`PropertiesConfig::load_from_str_with_override`],
+    /// returning the keys set to null along with the values.
+    ///
+    /// # Errors
+    /// * `Error::ParseError` - If the content is not valid PROPERTIES.
+    fn load_from_str_detailed(
+        content: &str,
+        excluded_keys: &HashSet<String>,
+    ) -> Result<Loaded, Error>
+    where
+        Self: Sized,
+    {
+        load_content(content, excluded_keys, parse_str)
+    }
+
+    /// Load PROPERTIES content from a reader, e.g. stdin, and initialize the structure.
+    ///
+    /// # Errors
+    /// * `Error::IoError` - If the reader fails or yields invalid UTF-8.
+    /// * `Error::ParseError` - If the content is not valid PROPERTIES.
+    fn load_from_reader<R: Read>(reader: R) -> Result<T, Error>
+    where
+        T: Default,
+        HashMap<String, String>: Into<T>,
+        Self: Sized,
+    {
+        Self::load_from_str(&misc::read_source(reader)?)
+    }
+}
+
+/// Parse a `.properties` document named `name` into `map`, keys are used as is.
+pub(crate) fn parse_str(content: &str, name: &str, map: &mut Loaded) -> Result<(), Error> {
+    let entries = parse_properties(content).map_err(|e| {
+        let location = Location::in_content(name, content, e.line, 1);
+        Error::parse_properties_error(name, e).with_location(location)
+    })?;
+    for (key, value, line, column) in entries {
+        // like java.util.Properties, the last occurrence of a key wins
+        let location = Location::in_content(name, content, line, column);
+        map.insert_located(key, value, location);
+    }
+
+    Ok(())
+}
+
+/// Malformed `.properties` content, e.g. an invalid `\uXXXX` escape.
+#[derive(Debug)]
+struct PropertiesError {
+    line: usize,
+    message: String,
+}
+
+impl fmt::Display for PropertiesError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for PropertiesError {}
+
+const WHITESPACE: [char; 3] = [' ', '\t', '\x0c'];
+
+/// Split the content into key/value pairs following the rules of `java.util.Properties::load`,
+/// with the line and column where each key starts.
+fn parse_properties(content: &str) -> Result<Vec<(String, String, usize, usize)>, PropertiesError> {
+    let mut entries = Vec::new();
+    let mut lines = content.lines().enumerate();
+
+    while let Some((index, raw)) = lines.next() {
+        let line = raw.trim_start_matches(WHITESPACE);
+        if line.is_empty() || line.starts_with('#') || line.starts_with('!') {
+            continue;
+        }
+
+        // a line ending with an odd number of backslashes continues on the next one
+        let mut logical = line.to_string();
+        while logical.chars().rev().take_while(|c| *c == '\\').count() % 2 == 1 {
+            logical.pop();
+            match lines.next() {
+                Some((_, next)) => logical.push_str(next.trim_start_matches(WHITESPACE)),
+                None => break,
+            }
+        }
+
+        let (key, value) = split_entry(&logical).map_err(|message| PropertiesError {
+            line: index + 1,
+            message,
+        })?;
+        let column = raw.chars().count() - line.chars().count() + 1;
+        entries.push((key, value, index + 1, column));
+    }
+
+    Ok(entries)
+}
+
+/// Split a logical line at the first unescaped `=`, `:` or whitespace.
+fn split_entry(line: &str) -> Result<(String, String), String> {
+    let mut chars = line.chars().peekable();
+
+    let mut key = String::new();
+    while let Some(&c) = chars.peek() {
+        match c {
+            '=' | ':' => break,
+            c if WHITESPACE.contains(&c) => break,
+            '\\' => {
+                chars.next();
+                key.push(unescape(&mut chars)?);
+            }
+            c => {
+                key.push(c);
+                chars.next();
+            }
+        }
+    }
+
+    // whitespace around a single `=` or `:` separator is not part of the value
+    while chars.next_if(|c| WHITESPACE.contains(c)).is_some() {}
+    if chars.next_if(|c| *c == '=' || *c == ':').is_some() {
+        while chars.next_if(|c| WHITESPACE.contains(c)).is_some() {}
+    }
+
+    let mut value = String::new();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            value.push(unescape(&mut chars)?);
+        } else {
+            value.push(c);
+        }
+    }
+
+    Ok((key, value))
+}
+
+/// Decode the escape sequence following a backslash.
+fn unescape(chars: &mut Peekable<Chars>) -> Result<char, String> {
+    match chars.next() {
+        Some('t') => Ok('\t'),
+        Some('n') => Ok('\n'),
+        Some('r') => Ok('\r'),
+        Some('f') => Ok('\x0c'),
+        Some('u') => {
+            let unit = read_utf16_unit(chars)?;
+            if (0xD800..0xDC00).contains(&unit) {
+                // a high surrogate must be followed by an escaped low surrogate
+                if chars.next() != Some('\\') || chars.next() != Some('u') {
+                    return Err("unpaired surrogate in \\uxxxx encoding".to_string());
+                }
+                let low = read_utf16_unit(chars)?;
+                char::decode_utf16([unit, low])
+                    .next()
+                    .and_then(Result::ok)
+                    .ok_or_else(|| "unpaired surrogate in \\uxxxx encoding".to_string())
+            } else {
+                char::from_u32(u32::from(unit))
+                    .ok_or_else(|| "unpaired surrogate in \\uxxxx encoding".to_string())
+            }
+        }
+        Some(c) => Ok(c),
+        None => Err("dangling escape at end of line".to_string()),
+    }
+}
+
+fn read_utf16_unit(chars: &mut Peekable<Chars>) -> Result<u16, String> {
+    let hex: String = chars.by_ref().take(4).collect();
+    if hex.len() != 4 {
+        return Err("malformed \\uxxxx encoding".to_string());
+    }
+    u16::from_str_radix(&hex, 16).map_err(|_| "malformed \\uxxxx encoding".to_string())
+}
@@ -0,0 +1,212 @@
+use crate::json::flatten_json_value;
+use crate::{load_content, load_files};
+use better_config_core::{misc, AbstractConfig, Error, Loaded, Location, PathPolicy};
+use ron::{Number, Value};
+use std::collections::{HashMap, HashSet};
+use std::io::Read;
+
+/// Indicates that structure can be initialized from RON file.
+pub trait RonConfig<T = HashMap<String, String>>: AbstractConfig<T> {
+    /// Target used when none is given.
+    const DEFAULT_TARGET: &'static str = "config.ron";
+
+    /// Whether files later in the target override earlier ones.
+    const LATER_TARGETS_OVERRIDE: bool = true;
+
+    /// Only load files that resolve inside this directory, set with
+    /// `#[env(RonConfig(sandbox_root = "/etc/app"))]`.
+    const SANDBOX_ROOT: Option<&'static str> = None;
+
+    /// Load specified RON file and initialize the structure.
+    ///
+    /// # Arguments
+    /// * `target` - Path to the RON file.
+    ///
+    /// # Errors
+    /// * `Error::LoadFileError` - If the specified RON file cannot be loaded or parsed.
+    fn load(target: Option<String>) -> Result<T, Error>
+    where
+        T: Default,
+        HashMap<String, String>: Into<T>,
+        Self: Sized,
+    {
+        Self::load_with_override(target, &HashSet::new())
+    }
+
+    /// Load specified RON file with explicit control over which keys should not be overridden.
+    ///
+    /// # Arguments
+    /// * `target` - Path to the RON file.
+    /// * `excluded_keys` - Keys that should not be overridden by environment variables.
+    ///
+    /// # Errors
+    /// * `Error::LoadFileError` - If the specified RON file cannot be loaded or parsed.
+    fn load_with_override(
+        target: Option<String>,
+        excluded_keys: &HashSet<String>,
+    ) -> Result<T, Error>
+    where
+        T: Default,
+        HashMap<String, String>: Into<T>,
+        Self: Sized,
+    {
+        Ok(Self::load_detailed(target, excluded_keys)?.values.into())
+    }
+
+    /// Load specified files like [`RonConfig::load_with_override`],
+    /// returning the keys set to null along with the values.
+    ///
+    /// # Errors
+    /// * `Error::LoadFileError` - If the specified RON file cannot be loaded or parsed.
+    fn load_detailed(
+        target: Option<String>,
+        excluded_keys: &HashSet<String>,
+    ) -> Result<Loaded, Error>
+    where
+        Self: Sized,
+    {
+        load_files(
+            &target.unwrap_or_else(|| Self::DEFAULT_TARGET.to_string()),
+            &["ron"],
+            &PathPolicy::from_root(Self::SANDBOX_ROOT),
+            excluded_keys,
+            parse_str,
+        )
+    }
+
+    /// Load RON content from a string and initialize the structure.
+    ///
+    /// # Arguments
+    /// * `content` - A RON document, e.g. embedded with `include_str!`.
+    ///
+    /// # Errors
+    /// * `Error::ParseError` - If the content is not valid RON.
+    fn load_from_str(content: &str) -> Result<T, Error>
+    where
+        T: Default,
+        HashMap<String, String>: Into<T>,
+        Self: Sized,
+    {
+        Self::load_from_str_with_override(content, &HashSet::new())
+    }
+
+    /// Load RON content from a string with explicit control over which keys should not be overridden.
+    ///
+    /// # Arguments
+    /// * `content` - A RON document.
+    /// * `excluded_keys` - Keys that should not be overridden by environment variables.
+    ///
+    /// # Errors
+    /// * `Error::ParseError` - If the content is not valid RON.
+    fn load_from_str_with_override(
+        content: &str,
+        excluded_keys: &HashSet<String>,
+    ) -> Result<T, Error>
+    where
+        T: Default,
+        HashMap<String, String>: Into<T>,
+        Self: Sized,
+    {
+        Ok(Self::load_from_str_detailed(content, excluded_keys)?
+            .values
+            .into())
+    }
+
+    /// Load content from a string like [`RonConfig::load_from_str_with_override`],
+    /// returning the keys set to null along with the values.
+    ///
+    /// # Errors
+    /// * `Error::ParseError` - If the content is not valid RON.
+    fn load_from_str_detailed(
+        content: &str,
+        excluded_keys: &HashSet<String>,
+    ) -> Result<Loaded, Error>
+    where
+        Self: Sized,
+    {
+        load_content(content, excluded_keys, parse_str)
+    }
+
+    /// Load RON content from a reader, e.g. stdin, and initialize the structure.
+    ///
+    /// # Errors
+    /// * `Error::IoError` - If the reader fails or yields invalid UTF-8.
+    /// * `Error::ParseError` - If the content is not valid RON.
+    fn load_from_reader<R: Read>(reader: R) -> Result<T, Error>
+    where
+        T: Default,
+        HashMap<String, String>: Into<T>,
+        Self: Sized,
+    {
+        Self::load_from_str(&misc::read_source(reader)?)
+    }
+}
+
+/// Parse a RON document named `name` and flatten it into `map`.
+pub(crate) fn parse_str(content: &str, name: &str, map: &mut Loaded) -> Result<(), Error> {
+    let value: Value = ron::from_str(content).map_err(|e| {
+        let location = Location::in_content(name, content, e.span.start.line, e.span.start.col);
+        Error::parse_ron_error(name, e).with_location(location)
+    })?;
+
+    let value = ron_to_json(value)
+        .map_err(|e| Error::value_conversion_error("ron", "string", &format!("{}", e)))?;
+    flatten_json_value(&value, None, map)
+        .map_err(|e| Error::value_conversion_error("ron", "string", &format!("{}", e)))
+}
+
+/// The RON value as JSON, so that it is flattened like JSON.
+///
+/// Non-string map keys are written as RON, `()` and `None` become null, and bytes become a
+/// lossy UTF-8 string.
+fn ron_to_json(value: Value) -> Result<serde_json::Value, ron::Error> {
+    Ok(match value {
+        // named and anonymous structs are maps with identifier keys
+        Value::Map(obj) => {
+            let mut map = serde_json::Map::new();
+            for (key, val) in obj {
+                let key = match key {
+                    Value::String(s) => s,
+                    other => ron::to_string(&other)?,
+                };
+                map.insert(key, ron_to_json(val)?);
+            }
+            serde_json::Value::Object(map)
+        }
+        Value::Seq(arr) => {
+            serde_json::Value::Array(arr.into_iter().map(ron_to_json).collect::<Result<_, _>>()?)
+        }
+        Value::Option(Some(val)) => ron_to_json(*val)?,
+        Value::Option(None) | Value::Unit => serde_json::Value::Null,
+        Value::String(s) => serde_json::Value::String(s),
+        Value::Char(c) => serde_json::Value::String(c.to_string()),
+        Value::Bool(b) => serde_json::Value::Bool(b),
+        Value::Bytes(bytes) => serde_json::Value::String(String::from_utf8_lossy(&bytes).into()),
+        Value::Number(n) => {
+            let number = match n {
+                Number::I8(v) => Some(v.into()),
+                Number::I16(v) => Some(v.into()),
+                Number::I32(v) => Some(v.into()),
+                Number::I64(v) => Some(v.into()),
+                Number::U8(v) => Some(v.into()),
+                Number::U16(v) => Some(v.into()),
+                Number::U32(v) => Some(v.into()),
+                Number::U64(v) => Some(v.into()),
+                // through the shortest text of the f32, so 1.1 stays 1.1
+                Number::F32(v) => v
+                    .get()
+                    .to_string()
+                    .parse()
+                    .ok()
+                    .and_then(serde_json::Number::from_f64),
+                Number::F64(v) => serde_json::Number::from_f64(v.get()),
+                _ => None,
+            };
+            match number {
+                Some(number) => serde_json::Value::Number(number),
+                // NaN and infinities have no JSON number
+                None => serde_json::Value::String(ron::to_string(&n)?),
+            }
+        }
+    })
+}
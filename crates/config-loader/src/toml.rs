@@ -1,14 +1,28 @@
-use better_config_core::{merge_with_env_uppercase, AbstractConfig, Error, misc};
+use crate::{load_content, load_files};
+use better_config_core::{misc, AbstractConfig, Error, Loaded, Location, PathPolicy};
 use std::collections::{HashMap, HashSet};
-use std::fs;
+use std::io::Read;
 use toml::Value;
+use toml_edit::{ImDocument, Item, Table, Value as EditValue};
 
 /// Indicates that structure can be initialized from TOML file.
 pub trait TomlConfig<T = HashMap<String, String>>: AbstractConfig<T> {
+    /// Target used when none is given.
+    const DEFAULT_TARGET: &'static str = "config.toml";
+
+    /// Whether files later in the target override earlier ones.
+    const LATER_TARGETS_OVERRIDE: bool = true;
+
+    /// Only load files that resolve inside this directory, set with
+    /// `#[env(TomlConfig(sandbox_root = "/etc/app"))]`.
+    const SANDBOX_ROOT: Option<&'static str> = None;
+
     /// Load specified TOML files and initialize the structure.
     ///
     /// # Arguments
-    /// * `target` - A comma-separated string of TOML file paths, e.g., "config.toml,local.toml".
+    /// * `target` - A comma-separated string of TOML file paths, e.g., "config.toml,local.toml?".
+    ///   Paths ending with `?` are optional and skipped when missing, globs such as
+    ///   `conf.d/*.toml` and directories are expanded in lexical order.
     ///
     /// # Errors
     /// * `Error::LoadFileError` - If any of the specified TOML files cannot be loaded or parsed.
@@ -24,73 +38,231 @@ pub trait TomlConfig<T = HashMap<String, String>>: AbstractConfig<T> {
     /// Load specified TOML files with explicit control over which keys should not be overridden.
     ///
     /// # Arguments
-    /// * `target` - A comma-separated string of TOML file paths, e.g., "config.toml,local.toml".
+    /// * `target` - A comma-separated string of TOML file paths, e.g., "config.toml,local.toml?".
+    ///   Paths ending with `?` are optional and skipped when missing.
     /// * `excluded_keys` - Keys that should not be overridden by environment variables.
     ///
     /// # Errors
     /// * `Error::LoadFileError` - If any of the specified TOML files cannot be loaded or parsed.
-    fn load_with_override(target: Option<String>, excluded_keys: &HashSet<String>) -> Result<T, Error>
+    fn load_with_override(
+        target: Option<String>,
+        excluded_keys: &HashSet<String>,
+    ) -> Result<T, Error>
     where
         T: Default,
         HashMap<String, String>: Into<T>,
         Self: Sized,
     {
-        let target = target.or(Some("config.toml".to_string()));
+        Ok(Self::load_detailed(target, excluded_keys)?.values.into())
+    }
 
-        let mut toml_map = HashMap::new();
+    /// Load specified files like [`TomlConfig::load_with_override`],
+    /// returning the keys set to null along with the values.
+    ///
+    /// # Errors
+    /// * `Error::LoadFileError` - If any of the specified TOML files cannot be loaded or parsed.
+    fn load_detailed(
+        target: Option<String>,
+        excluded_keys: &HashSet<String>,
+    ) -> Result<Loaded, Error>
+    where
+        Self: Sized,
+    {
+        load_files(
+            &target.unwrap_or_else(|| Self::DEFAULT_TARGET.to_string()),
+            &["toml"],
+            &PathPolicy::from_root(Self::SANDBOX_ROOT),
+            excluded_keys,
+            parse_str,
+        )
+    }
 
-        if let Some(target) = target {
-            let file_paths = misc::validate_and_split_paths(&target)?;
+    /// Load TOML content from a string and initialize the structure.
+    ///
+    /// # Arguments
+    /// * `content` - A TOML document, e.g. embedded with `include_str!`.
+    ///
+    /// # Errors
+    /// * `Error::ParseError` - If the content is not valid TOML.
+    fn load_from_str(content: &str) -> Result<T, Error>
+    where
+        T: Default,
+        HashMap<String, String>: Into<T>,
+        Self: Sized,
+    {
+        Self::load_from_str_with_override(content, &HashSet::new())
+    }
 
-            for file_path in file_paths {
-                // Check file accessibility before reading
-                misc::check_file_accessibility(&file_path)?;
+    /// Load TOML content from a string with explicit control over which keys should not be overridden.
+    ///
+    /// # Arguments
+    /// * `content` - A TOML document.
+    /// * `excluded_keys` - Keys that should not be overridden by environment variables.
+    ///
+    /// # Errors
+    /// * `Error::ParseError` - If the content is not valid TOML.
+    fn load_from_str_with_override(
+        content: &str,
+        excluded_keys: &HashSet<String>,
+    ) -> Result<T, Error>
+    where
+        T: Default,
+        HashMap<String, String>: Into<T>,
+        Self: Sized,
+    {
+        Ok(Self::load_from_str_detailed(content, excluded_keys)?
+            .values
+            .into())
+    }
 
-                let content = fs::read_to_string(&file_path)
-                    .map_err(|e| Error::IoError {
-                        operation: format!("read file '{}'", file_path),
-                        source: Some(Box::new(e)),
-                    })?;
+    /// Load content from a string like [`TomlConfig::load_from_str_with_override`],
+    /// returning the keys set to null along with the values.
+    ///
+    /// # Errors
+    /// * `Error::ParseError` - If the content is not valid TOML.
+    fn load_from_str_detailed(
+        content: &str,
+        excluded_keys: &HashSet<String>,
+    ) -> Result<Loaded, Error>
+    where
+        Self: Sized,
+    {
+        load_content(content, excluded_keys, parse_str)
+    }
 
-                let value: Value = toml::from_str(&content)
-                    .map_err(|e| Error::parse_toml_error(&file_path, e))?;
+    /// Load TOML content from a reader, e.g. stdin, and initialize the structure.
+    ///
+    /// # Errors
+    /// * `Error::IoError` - If the reader fails or yields invalid UTF-8.
+    /// * `Error::ParseError` - If the content is not valid TOML.
+    fn load_from_reader<R: Read>(reader: R) -> Result<T, Error>
+    where
+        T: Default,
+        HashMap<String, String>: Into<T>,
+        Self: Sized,
+    {
+        Self::load_from_str(&misc::read_source(reader)?)
+    }
+}
+
+/// Parse a TOML document named `name` and flatten it into `map`.
+pub(crate) fn parse_str(content: &str, name: &str, map: &mut Loaded) -> Result<(), Error> {
+    let value: Value = toml::from_str(content).map_err(|e| {
+        // the TOML error renders the offending line itself
+        let location = e.span().map(|span| Location {
+            snippet: None,
+            ..Location::from_offset(name, content, span.start)
+        });
+        Error::parse_toml_error(name, e).with_location(location)
+    })?;
+
+    if let Some(table) = value.as_table() {
+        flatten_table(table, None, map)
+            .map_err(|e| Error::value_conversion_error("toml", "string", &format!("{}", e)))?;
+    }
 
-                if let Some(table) = value.as_table() {
-                    flatten_table(table, None, &mut toml_map)
-                        .map_err(|e| Error::value_conversion_error("toml", "string", &format!("{}", e)))?;
+    // toml_edit keeps the spans that `toml::Value` drops
+    if let Ok(document) = ImDocument::parse(content) {
+        record_table_locations(document.as_table(), None, content, name, map);
+    }
+
+    Ok(())
+}
+
+/// Record where every flattened key of `table` is defined, with the keys of [`flatten_table`].
+fn record_table_locations(
+    table: &Table,
+    prefix: Option<&str>,
+    content: &str,
+    name: &str,
+    map: &mut Loaded,
+) {
+    for (key, item) in table.iter() {
+        let full_key = match prefix {
+            Some(p) => format!("{}.{}", p, key),
+            None => key.to_string(),
+        };
+        match item {
+            Item::Table(t) => record_table_locations(t, Some(&full_key), content, name, map),
+            Item::ArrayOfTables(tables) => {
+                for (i, t) in tables.iter().enumerate() {
+                    let key = format!("{}[{}]", full_key, i);
+                    record_table_locations(t, Some(&key), content, name, map);
                 }
             }
+            Item::Value(value) => record_value_locations(value, full_key, content, name, map),
+            Item::None => {}
         }
+    }
+}
 
-        // Apply environment variable override with excluded keys
-        let toml_map = merge_with_env_uppercase(toml_map, None, excluded_keys);
-
-        Ok(toml_map.into())
+fn record_value_locations(
+    value: &EditValue,
+    key: String,
+    content: &str,
+    name: &str,
+    map: &mut Loaded,
+) {
+    match value {
+        EditValue::InlineTable(t) => {
+            for (k, v) in t.iter() {
+                record_value_locations(v, format!("{}.{}", key, k), content, name, map);
+            }
+        }
+        EditValue::Array(arr) => {
+            for (i, v) in arr.iter().enumerate() {
+                record_value_locations(v, format!("{}[{}]", key, i), content, name, map);
+            }
+        }
+        _ => {
+            if let Some(span) = value.span() {
+                let location = Location::from_offset(name, content, span.start);
+                map.locations.insert(key, location);
+            }
+        }
     }
 }
 
 fn flatten_table(
     table: &toml::value::Table,
     prefix: Option<&str>,
-    map: &mut HashMap<String, String>,
+    map: &mut Loaded,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     for (key, value) in table {
         let full_key = match prefix {
             Some(p) => format!("{}.{}", p, key),
             None => key.clone(),
         };
-        match value {
-            toml::Value::Table(t) => flatten_table(t, Some(&full_key), map)?,
-            toml::Value::Array(_arr) => {
-                map.insert(full_key, value.to_string());
-            }
-            toml::Value::String(s) => {
-                map.insert(full_key, s.clone());
-            }
-            _ => {
-                map.insert(full_key, value.to_string());
+        flatten_value(value, full_key, map)?;
+    }
+
+    Ok(())
+}
+
+fn flatten_value(
+    value: &Value,
+    key: String,
+    map: &mut Loaded,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    match value {
+        // inline tables are tables as well
+        Value::Table(t) => flatten_table(t, Some(&key), map)?,
+        // arrays, including arrays of tables (`[[servers]]`), are indexed like JSON and YAML
+        Value::Array(arr) => {
+            for (i, val) in arr.iter().enumerate() {
+                flatten_value(val, format!("{}[{}]", key, i), map)?;
             }
         }
+        Value::String(s) => {
+            map.insert(key, s.clone());
+        }
+        // RFC 3339, e.g. `1979-05-27T07:32:00-08:00`, local dates and times have no offset
+        Value::Datetime(dt) => {
+            map.insert(key, dt.to_string());
+        }
+        _ => {
+            map.insert(key, value.to_string());
+        }
     }
 
     Ok(())
@@ -0,0 +1,241 @@
+use crate::{load_content, load_files, Source};
+use better_config_core::{misc, AbstractConfig, Error, Loaded, Location, PathPolicy};
+use roxmltree::{Document, Node};
+use std::collections::{HashMap, HashSet};
+use std::io::Read;
+
+/// Indicates that structure can be initialized from XML file.
+pub trait XmlConfig<T = HashMap<String, String>>: AbstractConfig<T> {
+    /// Target used when none is given.
+    const DEFAULT_TARGET: &'static str = "config.xml";
+
+    /// Whether files later in the target override earlier ones.
+    const LATER_TARGETS_OVERRIDE: bool = true;
+
+    /// Only load files that resolve inside this directory, set with
+    /// `#[env(XmlConfig(sandbox_root = "/etc/app"))]`.
+    const SANDBOX_ROOT: Option<&'static str> = None;
+
+    /// Marker put in front of attribute names, set with `#[env(XmlConfig(attr_prefix = "_"))]`.
+    const ATTR_PREFIX: &'static str = DEFAULT_ATTR_PREFIX;
+
+    /// Load specified XML file and initialize the structure.
+    ///
+    /// # Arguments
+    /// * `target` - Path to the XML file.
+    ///
+    /// # Errors
+    /// * `Error::LoadFileError` - If the specified XML file cannot be loaded or parsed.
+    fn load(target: Option<String>) -> Result<T, Error>
+    where
+        T: Default,
+        HashMap<String, String>: Into<T>,
+        Self: Sized,
+    {
+        Self::load_with_override(target, &HashSet::new())
+    }
+
+    /// Load specified XML file with explicit control over which keys should not be overridden.
+    ///
+    /// # Arguments
+    /// * `target` - Path to the XML file.
+    /// * `excluded_keys` - Keys that should not be overridden by environment variables.
+    ///
+    /// # Errors
+    /// * `Error::LoadFileError` - If the specified XML file cannot be loaded or parsed.
+    fn load_with_override(
+        target: Option<String>,
+        excluded_keys: &HashSet<String>,
+    ) -> Result<T, Error>
+    where
+        T: Default,
+        HashMap<String, String>: Into<T>,
+        Self: Sized,
+    {
+        Ok(Self::load_detailed(target, excluded_keys)?.values.into())
+    }
+
+    /// Load specified files like [`XmlConfig::load_with_override`],
+    /// returning the keys set to null along with the values.
+    ///
+    /// # Errors
+    /// * `Error::LoadFileError` - If the specified XML file cannot be loaded or parsed.
+    fn load_detailed(
+        target: Option<String>,
+        excluded_keys: &HashSet<String>,
+    ) -> Result<Loaded, Error>
+    where
+        Self: Sized,
+    {
+        load_files(
+            &target.unwrap_or_else(|| Self::DEFAULT_TARGET.to_string()),
+            &["xml"],
+            &PathPolicy::from_root(Self::SANDBOX_ROOT),
+            excluded_keys,
+            |content, name, map| parse_str_with_attr_prefix(content, name, Self::ATTR_PREFIX, map),
+        )
+    }
+
+    /// Load XML content from a string and initialize the structure.
+    ///
+    /// # Arguments
+    /// * `content` - A XML document, e.g. embedded with `include_str!`.
+    ///
+    /// # Errors
+    /// * `Error::ParseError` - If the content is not valid XML.
+    fn load_from_str(content: &str) -> Result<T, Error>
+    where
+        T: Default,
+        HashMap<String, String>: Into<T>,
+        Self: Sized,
+    {
+        Self::load_from_str_with_override(content, &HashSet::new())
+    }
+
+    /// Load XML content from a string with explicit control over which keys should not be overridden.
+    ///
+    /// # Arguments
+    /// * `content` - A XML document.
+    /// * `excluded_keys` - Keys that should not be overridden by environment variables.
+    ///
+    /// # Errors
+    /// * `Error::ParseError` - If the content is not valid XML.
+    fn load_from_str_with_override(
+        content: &str,
+        excluded_keys: &HashSet<String>,
+    ) -> Result<T, Error>
+    where
+        T: Default,
+        HashMap<String, String>: Into<T>,
+        Self: Sized,
+    {
+        Ok(Self::load_from_str_detailed(content, excluded_keys)?
+            .values
+            .into())
+    }
+
+    /// Load content from a string like [`XmlConfig::load_from_str_with_override`],
+    /// returning the keys set to null along with the values.
+    ///
+    /// # Errors
+    /// * `Error::ParseError` - If the content is not valid XML.
+    fn load_from_str_detailed(
+        content: &str,
+        excluded_keys: &HashSet<String>,
+    ) -> Result<Loaded, Error>
+    where
+        Self: Sized,
+    {
+        load_content(content, excluded_keys, |content, name, map| {
+            parse_str_with_attr_prefix(content, name, Self::ATTR_PREFIX, map)
+        })
+    }
+
+    /// Load XML content from a reader, e.g. stdin, and initialize the structure.
+    ///
+    /// # Errors
+    /// * `Error::IoError` - If the reader fails or yields invalid UTF-8.
+    /// * `Error::ParseError` - If the content is not valid XML.
+    fn load_from_reader<R: Read>(reader: R) -> Result<T, Error>
+    where
+        T: Default,
+        HashMap<String, String>: Into<T>,
+        Self: Sized,
+    {
+        Self::load_from_str(&misc::read_source(reader)?)
+    }
+}
+
+/// Default marker put in front of attribute names, `<server port="80">` becomes `server.@port`.
+pub(crate) const DEFAULT_ATTR_PREFIX: &str = "@";
+
+/// Parse an XML document named `name` and flatten it into `map`.
+pub(crate) fn parse_str(content: &str, name: &str, map: &mut Loaded) -> Result<(), Error> {
+    parse_str_with_attr_prefix(content, name, DEFAULT_ATTR_PREFIX, map)
+}
+
+/// Parse an XML document named `name` and flatten it into `map`, naming attributes
+/// `<element key>.<attr_prefix><attribute>`.
+///
+/// The root element is not part of the keys, like the top-level object of a JSON document.
+/// Repeated child elements become `key[i]` and the text of an element is its value.
+pub(crate) fn parse_str_with_attr_prefix(
+    content: &str,
+    name: &str,
+    attr_prefix: &str,
+    map: &mut Loaded,
+) -> Result<(), Error> {
+    let document = Document::parse(content).map_err(|e| {
+        let location =
+            Location::in_content(name, content, e.pos().row as usize, e.pos().col as usize);
+        Error::parse_xml_error(name, e).with_location(location)
+    })?;
+
+    let source = Source { content, name };
+    flatten_xml_node(document.root_element(), None, attr_prefix, &source, map);
+
+    Ok(())
+}
+
+fn flatten_xml_node(
+    node: Node,
+    parent_key: Option<String>,
+    attr_prefix: &str,
+    source: &Source,
+    map: &mut Loaded,
+) {
+    let child_key = |name: &str| match &parent_key {
+        Some(parent) => format!("{}.{}", parent, name),
+        None => name.to_string(),
+    };
+
+    for attr in node.attributes() {
+        let key = child_key(&format!("{}{}", attr_prefix, attr.name()));
+        let location = source.location(attr.range_value().start);
+        map.insert_located(key, attr.value().to_string(), location);
+    }
+
+    // group repeated elements by name, keeping the document order of their first occurrence
+    let mut children: Vec<(&str, Vec<Node>)> = Vec::new();
+    for child in node.children().filter(Node::is_element) {
+        let name = child.tag_name().name();
+        match children.iter_mut().find(|(n, _)| *n == name) {
+            Some((_, nodes)) => nodes.push(child),
+            None => children.push((name, vec![child])),
+        }
+    }
+
+    for (name, nodes) in &children {
+        let key = child_key(name);
+        if nodes.len() == 1 {
+            flatten_xml_node(nodes[0], Some(key), attr_prefix, source, map);
+        } else {
+            for (i, child) in nodes.iter().enumerate() {
+                let key = format!("{}[{}]", key, i);
+                flatten_xml_node(*child, Some(key), attr_prefix, source, map);
+            }
+        }
+    }
+
+    if let Some(key) = parent_key {
+        let text: String = node
+            .children()
+            .filter(Node::is_text)
+            .filter_map(|n| n.text())
+            .collect();
+        let text = text.trim();
+        if !text.is_empty() || (children.is_empty() && node.attributes().len() == 0) {
+            // the first non-blank text, or the element itself when it is empty
+            let offset = node
+                .children()
+                .filter(Node::is_text)
+                .find_map(|n| {
+                    let raw = n.text()?;
+                    let blank = raw.len() - raw.trim_start().len();
+                    (!raw.trim().is_empty()).then(|| n.range().start + blank)
+                })
+                .unwrap_or(node.range().start);
+            map.insert_located(key, text.to_string(), source.location(offset));
+        }
+    }
+}
@@ -1,9 +1,20 @@
-use better_config_core::{merge_with_env_uppercase, AbstractConfig, Error, misc};
+use crate::{load_content, load_files};
+use better_config_core::{misc, AbstractConfig, Error, Loaded, Location, PathPolicy};
 use std::collections::{HashMap, HashSet};
-use std::fs;
+use std::io::Read;
 
 /// Indicates that structure can be initialized from YAML/YML file.
 pub trait YmlConfig<T = HashMap<String, String>>: AbstractConfig<T> {
+    /// Target used when none is given.
+    const DEFAULT_TARGET: &'static str = "config.yml";
+
+    /// Whether files later in the target override earlier ones.
+    const LATER_TARGETS_OVERRIDE: bool = true;
+
+    /// Only load files that resolve inside this directory, set with
+    /// `#[env(YmlConfig(sandbox_root = "/etc/app"))]`.
+    const SANDBOX_ROOT: Option<&'static str> = None;
+
     /// Load specified YAML/YML file and initialize the structure.
     ///
     /// # Arguments
@@ -28,52 +39,133 @@ pub trait YmlConfig<T = HashMap<String, String>>: AbstractConfig<T> {
     ///
     /// # Errors
     /// * `Error::LoadFileError` - If the specified YAML/YML file cannot be loaded or parsed.
-    fn load_with_override(target: Option<String>, excluded_keys: &HashSet<String>) -> Result<T, Error>
+    fn load_with_override(
+        target: Option<String>,
+        excluded_keys: &HashSet<String>,
+    ) -> Result<T, Error>
     where
         T: Default,
         HashMap<String, String>: Into<T>,
         Self: Sized,
     {
-        let target = target.or(Some("config.yml".to_string()));
-
-        let mut yaml_map = HashMap::new();
+        Ok(Self::load_detailed(target, excluded_keys)?.values.into())
+    }
 
-        if let Some(target) = target {
-            let file_paths = misc::validate_and_split_paths(&target)?;
+    /// Load specified files like [`YmlConfig::load_with_override`],
+    /// returning the keys set to null along with the values.
+    ///
+    /// # Errors
+    /// * `Error::LoadFileError` - If the specified YAML/YML file cannot be loaded or parsed.
+    fn load_detailed(
+        target: Option<String>,
+        excluded_keys: &HashSet<String>,
+    ) -> Result<Loaded, Error>
+    where
+        Self: Sized,
+    {
+        load_files(
+            &target.unwrap_or_else(|| Self::DEFAULT_TARGET.to_string()),
+            &["yml", "yaml"],
+            &PathPolicy::from_root(Self::SANDBOX_ROOT),
+            excluded_keys,
+            parse_str,
+        )
+    }
 
-            for file_path in file_paths {
-                // Check file accessibility before reading
-                misc::check_file_accessibility(&file_path)?;
+    /// Load YAML content from a string and initialize the structure.
+    ///
+    /// # Arguments
+    /// * `content` - A YAML document, e.g. embedded with `include_str!`.
+    ///
+    /// # Errors
+    /// * `Error::ParseError` - If the content is not valid YAML.
+    fn load_from_str(content: &str) -> Result<T, Error>
+    where
+        T: Default,
+        HashMap<String, String>: Into<T>,
+        Self: Sized,
+    {
+        Self::load_from_str_with_override(content, &HashSet::new())
+    }
 
-                let content = fs::read_to_string(&file_path)
-                    .map_err(|e| Error::IoError {
-                        operation: format!("read file '{}'", file_path),
-                        source: Some(Box::new(e)),
-                    })?;
+    /// Load YAML content from a string with explicit control over which keys should not be overridden.
+    ///
+    /// # Arguments
+    /// * `content` - A YAML document.
+    /// * `excluded_keys` - Keys that should not be overridden by environment variables.
+    ///
+    /// # Errors
+    /// * `Error::ParseError` - If the content is not valid YAML.
+    fn load_from_str_with_override(
+        content: &str,
+        excluded_keys: &HashSet<String>,
+    ) -> Result<T, Error>
+    where
+        T: Default,
+        HashMap<String, String>: Into<T>,
+        Self: Sized,
+    {
+        Ok(Self::load_from_str_detailed(content, excluded_keys)?
+            .values
+            .into())
+    }
 
-                let value: serde_yml::Value = serde_yml::from_str(&content)
-                    .map_err(|e| Error::parse_yaml_error(&file_path, e))?;
+    /// Load content from a string like [`YmlConfig::load_from_str_with_override`],
+    /// returning the keys set to null along with the values.
+    ///
+    /// # Errors
+    /// * `Error::ParseError` - If the content is not valid YAML.
+    fn load_from_str_detailed(
+        content: &str,
+        excluded_keys: &HashSet<String>,
+    ) -> Result<Loaded, Error>
+    where
+        Self: Sized,
+    {
+        load_content(content, excluded_keys, parse_str)
+    }
 
-                flatten_yml_value(&value, None, &mut yaml_map)
-                    .map_err(|e| Error::value_conversion_error("yaml", "string", &format!("{}", e)))?;
-            }
-        }
+    /// Load YAML content from a reader, e.g. stdin, and initialize the structure.
+    ///
+    /// # Errors
+    /// * `Error::IoError` - If the reader fails or yields invalid UTF-8.
+    /// * `Error::ParseError` - If the content is not valid YAML.
+    fn load_from_reader<R: Read>(reader: R) -> Result<T, Error>
+    where
+        T: Default,
+        HashMap<String, String>: Into<T>,
+        Self: Sized,
+    {
+        Self::load_from_str(&misc::read_source(reader)?)
+    }
+}
 
-        // Apply environment variable override with excluded keys
-        let yaml_map = merge_with_env_uppercase(yaml_map, None, excluded_keys);
+/// Parse a YAML document named `name` and flatten it into `map`.
+pub(crate) fn parse_str(content: &str, name: &str, map: &mut Loaded) -> Result<(), Error> {
+    let mut value: serde_yml::Value = serde_yml::from_str(content).map_err(|e| {
+        let location = e
+            .location()
+            .map(|at| Location::from_offset(name, content, at.index()));
+        Error::parse_yaml_error(name, e).with_location(location)
+    })?;
+    // `<<: *base` merge keys, aliases are already resolved by the parser
+    value
+        .apply_merge()
+        .map_err(|e| Error::parse_yaml_error(name, e))?;
 
-        Ok(yaml_map.into())
-    }
+    flatten_yml_value(&value, None, map)
+        .map_err(|e| Error::value_conversion_error("yaml", "string", &format!("{}", e)))
 }
 
-fn flatten_yml_value(value: &serde_yml::Value, parent_key: Option<String>, map: &mut HashMap<String, String>) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+fn flatten_yml_value(
+    value: &serde_yml::Value,
+    parent_key: Option<String>,
+    map: &mut Loaded,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     match value {
         serde_yml::Value::Mapping(obj) => {
             for (key, val) in obj {
-                let key_str = match key {
-                    serde_yml::Value::String(s) => s.clone(),
-                    _ => serde_yml::to_string(key).unwrap_or_else(|_| "unknown".to_string()),
-                };
+                let key_str = yml_key_string(key);
                 let new_key = match &parent_key {
                     Some(parent) => format!("{}.{}", parent, key_str),
                     None => key_str,
@@ -116,9 +208,28 @@ fn flatten_yml_value(value: &serde_yml::Value, parent_key: Option<String>, map:
                 map.insert(key, b.to_string());
             }
         }
-        serde_yml::Value::Null => {}
-        _ => {}
+        serde_yml::Value::Null => {
+            if let Some(key) = parent_key {
+                map.insert_null(key);
+            }
+        }
+        // `!tag value`, the tag does not change the value
+        serde_yml::Value::Tagged(tagged) => flatten_yml_value(&tagged.value, parent_key, map)?,
     }
 
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Render a mapping key, non-string keys such as `8080:` or `true:` keep their plain form.
+fn yml_key_string(key: &serde_yml::Value) -> String {
+    match key {
+        serde_yml::Value::String(s) => s.clone(),
+        serde_yml::Value::Number(n) => n.to_string(),
+        serde_yml::Value::Bool(b) => b.to_string(),
+        serde_yml::Value::Null => "null".to_string(),
+        serde_yml::Value::Tagged(tagged) => yml_key_string(&tagged.value),
+        _ => serde_yml::to_string(key)
+            .map(|s| s.trim_end().to_string())
+            .unwrap_or_else(|_| "unknown".to_string()),
+    }
+}
@@ -3,11 +3,40 @@ pub use better_config_derive::*;
 
 #[cfg(feature = "env")]
 pub use better_config_loader::EnvConfig;
+#[cfg(feature = "file")]
+pub use better_config_loader::FileConfig;
+#[cfg(feature = "hcl")]
+pub use better_config_loader::HclConfig;
 #[cfg(feature = "ini")]
 pub use better_config_loader::IniConfig;
+#[cfg(feature = "json5")]
+pub use better_config_loader::Json5Config;
 #[cfg(feature = "json")]
 pub use better_config_loader::JsonConfig;
+#[cfg(feature = "kdl")]
+pub use better_config_loader::KdlConfig;
+#[cfg(feature = "properties")]
+pub use better_config_loader::PropertiesConfig;
+#[cfg(feature = "ron")]
+pub use better_config_loader::RonConfig;
 #[cfg(feature = "toml")]
 pub use better_config_loader::TomlConfig;
+#[cfg(feature = "xml")]
+pub use better_config_loader::XmlConfig;
 #[cfg(feature = "yml")]
 pub use better_config_loader::YmlConfig;
+#[cfg(any(
+    feature = "env",
+    feature = "toml",
+    feature = "json",
+    feature = "yml",
+    feature = "ini",
+    feature = "json5",
+    feature = "ron",
+    feature = "hcl",
+    feature = "properties",
+    feature = "xml",
+    feature = "kdl",
+    feature = "file"
+))]
+pub use better_config_loader::{detect_format, parse_str};
@@ -0,0 +1,124 @@
+use better_config::{env, TomlConfig};
+
+#[env(TomlConfig(target = "config-legacy.toml", deny_unknown_keys))]
+pub struct LegacyConfig {
+    #[conf(from = "db.host", aliases("database.host", "LEGACY_DB_HOST"))]
+    pub host: String,
+    #[conf(
+        from = "database.timeout",
+        default = "10",
+        deprecated = "use db.timeout_ms"
+    )]
+    pub timeout: u32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use better_config::{reset_warning_sink, set_warning_sink, Error};
+    use serial_test::serial;
+    use std::env;
+    use std::sync::{Arc, Mutex};
+
+    fn collect_warnings() -> Arc<Mutex<Vec<String>>> {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let sink_seen = seen.clone();
+        set_warning_sink(move |w| sink_seen.lock().unwrap().push(w.to_string()));
+        seen
+    }
+
+    #[test]
+    #[serial]
+    fn old_names_are_read_with_warnings() {
+        env::remove_var("LEGACY_DB_HOST");
+        let seen = collect_warnings();
+
+        let config = LegacyConfig::builder().build().unwrap();
+        reset_warning_sink();
+
+        assert_eq!(config.host, "legacy-db");
+        assert_eq!(config.timeout, 30);
+        assert_eq!(
+            *seen.lock().unwrap(),
+            [
+                "config-legacy.toml:2:8: key 'database.host' is deprecated, use 'db.host'",
+                "config-legacy.toml:3:11: key 'database.timeout' is deprecated: use db.timeout_ms",
+            ]
+        );
+        assert!(LegacyConfig::known_keys().contains(&"LEGACY_DB_HOST".to_string()));
+    }
+
+    // without the `log` feature the default sink prints to stderr, checked in a child process
+    // as the test harness captures the output of the tests
+    #[test]
+    #[serial]
+    #[cfg(not(feature = "log"))]
+    fn warnings_print_to_stderr_by_default() {
+        if env::var_os("BETTER_CONFIG_WARN_CHILD").is_some() {
+            LegacyConfig::builder().build().unwrap();
+            return;
+        }
+
+        env::remove_var("LEGACY_DB_HOST");
+        let output = std::process::Command::new(env::current_exe().unwrap())
+            .args([
+                "--exact",
+                "tests::warnings_print_to_stderr_by_default",
+                "--nocapture",
+            ])
+            .env("BETTER_CONFIG_WARN_CHILD", "1")
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(
+            stderr.contains(
+                "warning: config-legacy.toml:2:8: key 'database.host' is deprecated, use 'db.host'"
+            ),
+            "{}",
+            stderr
+        );
+        assert!(
+            stderr.contains(
+                "warning: config-legacy.toml:3:11: key 'database.timeout' is deprecated: use db.timeout_ms"
+            ),
+            "{}",
+            stderr
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn conflicting_names_fail() {
+        env::set_var("LEGACY_DB_HOST", "other-db");
+        let seen = collect_warnings();
+
+        let err = LegacyConfig::builder().build().err().unwrap();
+        reset_warning_sink();
+        env::remove_var("LEGACY_DB_HOST");
+
+        assert!(matches!(err, Error::ValidationError { .. }));
+        assert_eq!(
+            err.to_string(),
+            "Configuration validation failed: conflicting values for 'db.host': 'legacy-db' from 'database.host' and 'other-db' from 'LEGACY_DB_HOST'"
+        );
+        assert_eq!(seen.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    #[serial]
+    fn new_name_wins_silently() {
+        env::remove_var("LEGACY_DB_HOST");
+        let seen = collect_warnings();
+
+        let config = LegacyConfig::builder()
+            .source_str("[db]\nhost = \"new-db\"\n")
+            .build()
+            .unwrap();
+        reset_warning_sink();
+
+        assert_eq!(config.host, "new-db");
+        assert_eq!(config.timeout, 10);
+        assert!(seen.lock().unwrap().is_empty());
+    }
+}
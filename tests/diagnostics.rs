@@ -0,0 +1,70 @@
+use better_config::{env, TomlConfig};
+
+#[env(TomlConfig(target = "config-invalid.toml"))]
+pub struct InvalidConfig {
+    #[conf(from = "database.port", default = "5432")]
+    pub port: u16,
+}
+
+#[env(TomlConfig(target = "config-missing.toml"))]
+pub struct MissingConfig {
+    #[conf(from = "database.port", default = "5432")]
+    pub port: u16,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use miette::{Diagnostic, NarratableReportHandler};
+    use serial_test::serial;
+    use std::env;
+
+    #[test]
+    #[serial]
+    fn value_error_diagnostic() {
+        env::remove_var("DATABASE_PORT");
+
+        let err = InvalidConfig::builder().build().err().unwrap();
+        assert_eq!(err.code().unwrap().to_string(), "better_config::value");
+        assert_eq!(
+            err.help().unwrap().to_string(),
+            "set `database.port` to a valid u16"
+        );
+
+        let label = err.labels().unwrap().next().unwrap();
+        assert_eq!(label.label(), Some("expected u16"));
+        let contents = err
+            .source_code()
+            .unwrap()
+            .read_span(label.inner(), 0, 0)
+            .unwrap();
+        assert_eq!(contents.name(), Some("config-invalid.toml"));
+        assert_eq!(contents.line() + 1, 3);
+        let snippet = std::str::from_utf8(contents.data()).unwrap();
+        assert_eq!(
+            &snippet[label.offset()..label.offset() + label.len()],
+            "\"abc\""
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn report_shows_the_snippet_once() {
+        env::remove_var("DATABASE_PORT");
+
+        let err = InvalidConfig::builder().build().err().unwrap();
+        let mut report = String::new();
+        NarratableReportHandler::new()
+            .render_report(&mut report, &err)
+            .unwrap();
+        assert_eq!(report.matches("port = \"abc\"").count(), 1);
+    }
+
+    #[test]
+    fn load_file_diagnostic() {
+        let err = MissingConfig::builder().build().err().unwrap();
+        assert_eq!(err.code().unwrap().to_string(), "better_config::load_file");
+        assert!(err.help().is_some());
+        assert!(err.source_code().is_none());
+    }
+}
@@ -0,0 +1,86 @@
+use better_config::{env, TomlConfig};
+
+#[env(TomlConfig(target = "config.toml", embedded_defaults = "defaults.toml"))]
+pub struct AppConfig {
+    #[conf(from = "title")]
+    pub title: String,
+    #[conf(from = "server.host")]
+    pub host: String,
+    #[conf(from = "server.workers")]
+    pub workers: u32,
+}
+
+#[env(TomlConfig(target = "config.missing.toml?", embedded_defaults = "defaults.toml"))]
+pub struct DefaultsOnlyConfig {
+    #[conf(from = "server.port")]
+    pub port: u16,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+    use std::env;
+
+    fn fixture() {
+        env::remove_var("TITLE");
+        env::remove_var("SERVER_HOST");
+        env::remove_var("SERVER_WORKERS");
+        env::remove_var("SERVER_PORT");
+    }
+
+    #[test]
+    #[serial]
+    fn target_overrides_embedded_defaults() {
+        fixture();
+        let config = AppConfig::builder().build().unwrap();
+        // config.toml sets title, the other keys come from the embedded defaults
+        assert_eq!(config.title, "TOML Example");
+        assert_eq!(config.host, "0.0.0.0");
+        assert_eq!(config.workers, 4);
+    }
+
+    #[test]
+    #[serial]
+    fn embedded_defaults_without_target() {
+        fixture();
+        let config = DefaultsOnlyConfig::builder().build().unwrap();
+        assert_eq!(config.port, 8000);
+    }
+
+    #[test]
+    #[serial]
+    fn env_overrides_embedded_defaults() {
+        fixture();
+        env::set_var("SERVER_PORT", "9000");
+        let config = DefaultsOnlyConfig::builder().build().unwrap();
+        assert_eq!(config.port, 9000);
+        fixture();
+    }
+
+    #[test]
+    #[serial]
+    fn source_str_overrides_embedded_defaults() {
+        fixture();
+        let config = AppConfig::builder()
+            .source_str("[server]\nworkers = 8")
+            .build()
+            .unwrap();
+        // the in-memory source sets workers, the other keys come from the embedded defaults
+        assert_eq!(config.title, "Embedded Defaults");
+        assert_eq!(config.host, "0.0.0.0");
+        assert_eq!(config.workers, 8);
+    }
+
+    #[test]
+    fn malformed_defaults_fail_compilation() {
+        let cases = trybuild::TestCases::new();
+        cases.compile_fail("tests/ui/malformed-defaults.rs");
+    }
+
+    #[test]
+    fn loader_options_with_embedded_defaults_fail_compilation() {
+        let cases = trybuild::TestCases::new();
+        cases.compile_fail("tests/ui/embedded-defaults-loader-option.rs");
+    }
+}
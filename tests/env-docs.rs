@@ -0,0 +1,113 @@
+use better_config::{env, EnvConfig, TomlConfig};
+
+#[env(TomlConfig)]
+pub struct AppConfig {
+    /// Name shown in logs
+    #[conf(from = "name", default = "app")]
+    pub name: String,
+    #[conf(from = "build.id", default = "dev", no_env_override)]
+    pub build_id: String,
+    #[env]
+    pub database: DatabaseConfig,
+    #[env]
+    pub cache: CacheConfig,
+}
+
+#[env(TomlConfig(prefix = "database."))]
+pub struct DatabaseConfig {
+    /// Host of the primary
+    #[conf(from = "host")]
+    pub host: String,
+    #[conf(from = "port", default = "5432")]
+    pub port: Option<u16>,
+    #[conf(from = "password", default = "postgres", secret)]
+    pub password: String,
+}
+
+#[env(EnvConfig(prefix = "CACHE_"))]
+pub struct CacheConfig {
+    /// Time to live in seconds
+    #[conf(from = "TTL", default = "60")]
+    pub ttl: u64,
+}
+
+#[env(TomlConfig(prefix = "server."))]
+pub struct PlainFieldConfig {
+    pub host: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+    use std::env;
+
+    #[test]
+    fn test_env_docs() {
+        assert_eq!(
+            AppConfig::env_docs(),
+            "| Variable | Type | Default | Required | Description |\n\
+             | --- | --- | --- | --- | --- |\n\
+             | `NAME` | `String` | `app` | no | Name shown in logs |\n\
+             | `DATABASE_HOST` | `String` |  | yes | Host of the primary |\n\
+             | `DATABASE_PORT` | `u16` | `5432` | no |  |\n\
+             | `DATABASE_PASSWORD` | `String` |  | no |  |\n\
+             | `CACHE_TTL` | `u64` | `60` | no | Time to live in seconds |\n\
+             \n\
+             A variable only overrides a key that is set in the loaded configuration, \
+             it does not add a missing key.\n"
+        );
+    }
+
+    #[test]
+    fn test_env_docs_of_env_config() {
+        assert_eq!(
+            CacheConfig::env_docs(),
+            "| Variable | Type | Default | Required | Description |\n\
+             | --- | --- | --- | --- | --- |\n\
+             | `CACHE_TTL` | `u64` | `60` | no | Time to live in seconds |\n"
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn test_documented_variable_overrides_the_key() {
+        env::set_var("DATABASE_PORT", "6543");
+        let config = DatabaseConfig::builder()
+            .source_str("[database]\nhost = \"db\"\nport = 5432\n")
+            .build()
+            .unwrap();
+        env::remove_var("DATABASE_PORT");
+
+        assert!(DatabaseConfig::env_docs().contains("| `DATABASE_PORT` |"));
+        assert_eq!(config.port, Some(6543));
+    }
+
+    #[test]
+    #[serial]
+    fn test_documented_variable_does_not_add_a_missing_key() {
+        env::set_var("DATABASE_PORT", "6543");
+        let config = DatabaseConfig::builder()
+            .source_str("[database]\nhost = \"db\"\n")
+            .build()
+            .unwrap();
+        env::remove_var("DATABASE_PORT");
+
+        assert_eq!(config.port, Some(5432));
+    }
+
+    #[test]
+    #[serial]
+    fn test_field_without_conf_is_not_documented() {
+        assert!(!PlainFieldConfig::env_docs().contains("HOST"));
+
+        env::set_var("HOST", "replica");
+        let config = PlainFieldConfig::builder()
+            .source_str("[server]\nhost = \"db\"\n")
+            .build()
+            .unwrap();
+        env::remove_var("HOST");
+
+        assert_eq!(config.host, "replica");
+    }
+}
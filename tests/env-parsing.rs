@@ -0,0 +1,155 @@
+use better_config::{env, EnvConfig};
+
+#[env(EnvConfig(prefix = "PARSE_", target = ".env.parsing"))]
+pub struct ParsingConfig {
+    #[conf(from = "HOST")]
+    pub host: String,
+    #[conf(from = "PORT")]
+    pub port: u16,
+    #[conf(from = "URL")]
+    pub url: String,
+    #[conf(from = "LITERAL")]
+    pub literal: String,
+    #[conf(from = "GREETING")]
+    pub greeting: String,
+    #[conf(from = "CERT")]
+    pub cert: String,
+    #[conf(from = "FALLBACK")]
+    pub fallback: String,
+    #[conf(from = "PRICE")]
+    pub price: String,
+}
+
+#[env(EnvConfig(prefix = "PARSE_", target = ".env.parsing", expand = false))]
+pub struct RawConfig {
+    #[conf(from = "URL")]
+    pub url: String,
+    #[conf(from = "FALLBACK")]
+    pub fallback: String,
+    #[conf(from = "PRICE")]
+    pub price: String,
+}
+
+#[env(EnvConfig(prefix = "PARSE_", target = ".env.parsing", override_existing))]
+pub struct OverrideConfig {
+    #[conf(from = "HOST")]
+    pub host: String,
+    #[conf(from = "URL")]
+    pub url: String,
+}
+
+#[env(EnvConfig(prefix = "STRICT_", target = ".env.strict"))]
+pub struct LenientConfig {
+    #[conf(from = "FIRST")]
+    pub first: u8,
+    #[conf(from = "SECOND")]
+    pub second: u8,
+}
+
+#[env(EnvConfig(prefix = "STRICT_", target = ".env.strict", strict))]
+pub struct StrictConfig {
+    #[conf(from = "FIRST")]
+    pub first: u8,
+    #[conf(from = "SECOND")]
+    pub second: u8,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+    use std::env;
+
+    fn fixture() {
+        for key in [
+            "PARSE_HOST",
+            "PARSE_PORT",
+            "PARSE_URL",
+            "PARSE_LITERAL",
+            "PARSE_GREETING",
+            "PARSE_CERT",
+            "PARSE_FALLBACK",
+            "PARSE_PRICE",
+            "STRICT_FIRST",
+            "STRICT_SECOND",
+        ] {
+            env::remove_var(key);
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn export_quotes_and_expansion() {
+        fixture();
+
+        let config = ParsingConfig::builder().build().unwrap();
+        assert_eq!(config.host, "localhost");
+        assert_eq!(config.port, 8080);
+        assert_eq!(config.url, "http://localhost:8080/api");
+        assert_eq!(config.literal, "no ${PARSE_HOST} here");
+        assert_eq!(config.greeting, "hello\tworld");
+        assert_eq!(config.cert, "-----BEGIN-----\nabc\n-----END-----");
+        assert_eq!(config.fallback, "fallback");
+        assert_eq!(config.price, "$5");
+    }
+
+    #[test]
+    #[serial]
+    fn expansion_disabled() {
+        fixture();
+
+        let config = RawConfig::builder().build().unwrap();
+        assert_eq!(config.url, "http://${PARSE_HOST}:$PARSE_PORT/api");
+        assert_eq!(config.fallback, "${PARSE_MISSING:-fallback}");
+        assert_eq!(config.price, "\\$5");
+    }
+
+    #[test]
+    #[serial]
+    fn existing_vars_are_kept() {
+        fixture();
+        env::set_var("PARSE_HOST", "example.com");
+
+        let config = ParsingConfig::builder().build().unwrap();
+        assert_eq!(config.host, "example.com");
+        assert_eq!(config.url, "http://example.com:8080/api");
+    }
+
+    #[test]
+    #[serial]
+    fn existing_vars_are_overridden() {
+        fixture();
+        env::set_var("PARSE_HOST", "example.com");
+        env::set_var("PARSE_URL", "http://example.com");
+
+        let config = OverrideConfig::builder().build().unwrap();
+        assert_eq!(config.host, "localhost");
+        assert_eq!(config.url, "http://localhost:8080/api");
+    }
+
+    #[test]
+    #[serial]
+    fn lenient_skips_invalid_lines() {
+        fixture();
+
+        let config = LenientConfig::builder().build().unwrap();
+        assert_eq!(config.first, 1);
+        assert_eq!(config.second, 2);
+    }
+
+    #[test]
+    #[serial]
+    fn strict_rejects_invalid_lines() {
+        fixture();
+
+        let error = StrictConfig::builder().build().err().unwrap().to_string();
+        assert!(error.contains(".env.strict"), "{}", error);
+        assert!(error.contains("line 2"), "{}", error);
+    }
+
+    #[test]
+    fn unknown_loader_options_fail_compilation() {
+        let cases = trybuild::TestCases::new();
+        cases.compile_fail("tests/ui/unknown-loader-option.rs");
+    }
+}
@@ -0,0 +1,216 @@
+use better_config::{
+    env, JsonConfig, KdlConfig, PropertiesConfig, TomlConfig, XmlConfig, YmlConfig,
+};
+
+#[env(TomlConfig(target = "config-invalid.toml"))]
+pub struct InvalidConfig {
+    #[conf(from = "database.host")]
+    pub host: String,
+    #[conf(from = "database.port", default = "5432")]
+    pub port: u16,
+}
+
+#[env(JsonConfig)]
+pub struct JsonAppConfig {
+    #[conf(from = "name", default = "app")]
+    pub name: String,
+}
+
+#[env(JsonConfig)]
+pub struct JsonPortConfig {
+    #[conf(from = "server.port", default = "80")]
+    pub port: u16,
+}
+
+#[env(YmlConfig)]
+pub struct YmlPortConfig {
+    #[conf(from = "server.port", default = "80")]
+    pub port: u16,
+}
+
+#[env(PropertiesConfig)]
+pub struct PropertiesAppConfig {
+    #[conf(from = "server.port")]
+    pub port: Option<u16>,
+}
+
+#[env(XmlConfig)]
+pub struct XmlAppConfig {
+    #[conf(from = "server.port", default = "80")]
+    pub port: u16,
+    #[conf(from = "server.@workers", default = "1")]
+    pub workers: u8,
+}
+
+#[env(KdlConfig)]
+pub struct KdlAppConfig {
+    #[conf(from = "server.port", default = "80")]
+    pub port: u16,
+    #[conf(from = "server.workers", default = "1")]
+    pub workers: u8,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use better_config::Error;
+    use serial_test::serial;
+    use std::collections::HashSet;
+    use std::env;
+
+    #[test]
+    #[serial]
+    fn value_error_points_at_the_value() {
+        env::remove_var("DATABASE_PORT");
+
+        let err = InvalidConfig::builder().build().err().unwrap();
+        assert!(matches!(err, Error::ValueError { .. }));
+
+        let location = err.location().unwrap();
+        assert_eq!(location.name, "config-invalid.toml");
+        assert_eq!((location.line, location.column), (3, 8));
+        assert_eq!(
+            err.to_string(),
+            "config-invalid.toml:3:8: Invalid value for key 'database.port': expected u16, got 'abc'"
+        );
+        assert_eq!(
+            format!("{:#}", err),
+            "config-invalid.toml:3:8: Invalid value for key 'database.port': expected u16, got 'abc'\n  |\n3 | port = \"abc\"\n  |        ^"
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn env_override_has_no_location() {
+        env::set_var("DATABASE_PORT", "none");
+
+        let err = InvalidConfig::builder().build().err().unwrap();
+        assert!(err.location().is_none());
+        assert_eq!(
+            err.to_string(),
+            "Invalid value for key 'database.port': expected u16, got 'none'"
+        );
+
+        env::set_var("DATABASE_PORT", "6543");
+        let config = InvalidConfig::builder().build().unwrap();
+        assert_eq!(config.port, 6543);
+
+        env::remove_var("DATABASE_PORT");
+    }
+
+    #[test]
+    #[serial]
+    fn loaded_values_carry_their_locations() {
+        env::remove_var("DATABASE_PORT");
+        let target = Some("config-invalid.toml".to_string());
+
+        let loaded =
+            <InvalidConfig as TomlConfig>::load_detailed(target.clone(), &HashSet::new()).unwrap();
+        assert_eq!(
+            loaded.locations["database.port"].to_string(),
+            "config-invalid.toml:3:8"
+        );
+
+        // the overridden value no longer comes from the file
+        env::set_var("DATABASE_PORT", "6543");
+        let loaded = <InvalidConfig as TomlConfig>::load_detailed(target, &HashSet::new()).unwrap();
+        assert!(!loaded.locations.contains_key("database.port"));
+        assert!(loaded.locations.contains_key("database.host"));
+
+        env::remove_var("DATABASE_PORT");
+    }
+
+    #[test]
+    fn parse_error_location() {
+        let err = JsonAppConfig::builder()
+            .source_str("{\n  \"name\": \"app\",\n  oops\n}")
+            .build()
+            .err()
+            .unwrap();
+        assert!(matches!(err, Error::ParseError { .. }));
+
+        let location = err.location().unwrap();
+        assert_eq!(location.name, "<string>");
+        assert_eq!(location.line, 3);
+        assert_eq!(location.snippet.as_deref(), Some("  oops"));
+    }
+
+    #[test]
+    fn json_and_yml_value_errors_have_no_location() {
+        let err = JsonPortConfig::builder()
+            .source_str("{\n  \"server\": {\n    \"port\": \"eighty\"\n  }\n}")
+            .build()
+            .err()
+            .unwrap();
+        assert!(matches!(err, Error::ValueError { .. }));
+        assert!(err.location().is_none());
+        assert_eq!(
+            err.to_string(),
+            "Invalid value for key 'server.port': expected u16, got 'eighty'"
+        );
+
+        let err = YmlPortConfig::builder()
+            .source_str("server:\n  port: eighty\n")
+            .build()
+            .err()
+            .unwrap();
+        assert!(matches!(err, Error::ValueError { .. }));
+        assert!(err.location().is_none());
+    }
+
+    #[test]
+    fn option_value_error_location() {
+        let err = PropertiesAppConfig::builder()
+            .source_str("# server\n  server.port = eighty\n")
+            .build()
+            .err()
+            .unwrap();
+
+        let location = err.location().unwrap();
+        assert_eq!((location.line, location.column), (2, 3));
+        assert!(err.to_string().contains("expected u16, got 'eighty'"));
+    }
+
+    #[test]
+    fn xml_value_error_location() {
+        let err = XmlAppConfig::builder()
+            .source_str("<config>\n  <server>\n    <port> eighty </port>\n  </server>\n</config>")
+            .build()
+            .err()
+            .unwrap();
+        let location = err.location().unwrap();
+        assert_eq!((location.line, location.column), (3, 12));
+        assert_eq!(
+            location.snippet.as_deref(),
+            Some("    <port> eighty </port>")
+        );
+
+        let err = XmlAppConfig::builder()
+            .source_str("<config>\n  <server workers=\"many\"/>\n</config>")
+            .build()
+            .err()
+            .unwrap();
+        let location = err.location().unwrap();
+        assert_eq!((location.line, location.column), (2, 20));
+    }
+
+    #[test]
+    fn kdl_value_error_location() {
+        let err = KdlAppConfig::builder()
+            .source_str("server {\n    port \"eighty\"\n}\n")
+            .build()
+            .err()
+            .unwrap();
+        let location = err.location().unwrap();
+        assert_eq!((location.line, location.column), (2, 10));
+        assert_eq!(location.snippet.as_deref(), Some("    port \"eighty\""));
+
+        let err = KdlAppConfig::builder()
+            .source_str("server port=80 workers=many\n")
+            .build()
+            .err()
+            .unwrap();
+        let location = err.location().unwrap();
+        assert_eq!((location.line, location.column), (1, 16));
+    }
+}
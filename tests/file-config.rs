@@ -0,0 +1,77 @@
+use better_config::{env, FileConfig};
+
+#[env(FileConfig(target = "config.toml,config.yml"))]
+pub struct MixedConfig {
+    #[conf(from = "title", default = "default-title")]
+    pub title: String,
+    #[conf(from = "owner.name", default = "nobody")]
+    pub owner: String,
+    #[conf(from = "database.host", default = "localhost")]
+    pub db_host: String,
+}
+
+#[env(FileConfig(target = "appconfig"))]
+pub struct DetectedConfig {
+    #[conf(from = "name", default = "default-name")]
+    pub name: String,
+    #[conf(from = "server.port", default = "80")]
+    pub port: u16,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use better_config::{detect_format, Format};
+    use serial_test::serial;
+    use std::env;
+
+    fn fixture() {
+        env::remove_var("TITLE");
+        env::remove_var("OWNER_NAME");
+        env::remove_var("DATABASE_HOST");
+        env::remove_var("NAME");
+        env::remove_var("SERVER_PORT");
+    }
+
+    #[test]
+    #[serial]
+    fn mixed_formats_later_file_wins() {
+        fixture();
+        let config = MixedConfig::builder().build().unwrap();
+        assert_eq!(config.title, "Yml Example");
+        assert_eq!(config.owner, "Tom Preston-Werner");
+        assert_eq!(config.db_host, "127.0.0.1");
+    }
+
+    #[test]
+    #[serial]
+    fn format_detected_without_extension() {
+        fixture();
+        let config = DetectedConfig::builder().build().unwrap();
+        assert_eq!(config.name, "detected");
+        assert_eq!(config.port, 7000);
+    }
+
+    #[test]
+    #[serial]
+    fn source_str_is_detected() {
+        fixture();
+        let config = DetectedConfig::builder()
+            .source_str(r#"{"name": "from-json", "server": {"port": 7100}}"#)
+            .build()
+            .unwrap();
+        assert_eq!(config.name, "from-json");
+        assert_eq!(config.port, 7100);
+    }
+
+    #[test]
+    fn detect_format_order() {
+        assert_eq!(detect_format(r#"{"a": 1}"#), Some(Format::Json));
+        assert_eq!(
+            detect_format("title = \"x\"\n[a]\nb = 1"),
+            Some(Format::Toml)
+        );
+        assert_eq!(detect_format("a:\n  b: 1"), Some(Format::Yml));
+        assert_eq!(detect_format("[a]\nb = some value"), Some(Format::Ini));
+    }
+}
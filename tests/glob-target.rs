@@ -0,0 +1,69 @@
+use better_config::{env, TomlConfig};
+
+#[env(TomlConfig(target = "conf.d/*.toml"))]
+pub struct GlobAppConfig {
+    #[conf(from = "database.host", default = "localhost")]
+    pub host: String,
+    #[conf(from = "database.port", default = "3306")]
+    pub port: u16,
+    #[conf(from = "cache.ttl", default = "0")]
+    pub ttl: u32,
+}
+
+#[env(TomlConfig(target = "config.toml,conf.d"))]
+pub struct DirAppConfig {
+    #[conf(from = "title", default = "hello toml")]
+    pub title: String,
+    #[conf(from = "database.port", default = "3306")]
+    pub port: u16,
+}
+
+#[env(TomlConfig(target = "conf.d/*.json"))]
+pub struct NoMatchAppConfig {
+    #[conf(from = "title", default = "hello toml")]
+    pub title: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+    use std::env;
+
+    fn fixture() {
+        env::remove_var("DATABASE_HOST");
+        env::remove_var("DATABASE_PORT");
+        env::remove_var("CACHE_TTL");
+    }
+
+    #[test]
+    #[serial]
+    fn glob_merges_in_lexical_order() {
+        fixture();
+        let config = GlobAppConfig::builder().build().unwrap();
+        assert_eq!(config.host, "127.0.0.1");
+        // 30-override.toml is loaded after 10-db.toml
+        assert_eq!(config.port, 6432);
+        assert_eq!(config.ttl, 60);
+    }
+
+    #[test]
+    #[serial]
+    fn directory_skips_other_extensions() {
+        fixture();
+        let config = DirAppConfig::builder().build().unwrap();
+        assert_eq!(config.title, "TOML Example");
+        assert_eq!(config.port, 6432);
+    }
+
+    #[test]
+    #[serial]
+    fn glob_without_matches_fails() {
+        fixture();
+        let result = NoMatchAppConfig::builder().build();
+        assert!(matches!(
+            result,
+            Err(better_config::Error::LoadFileError { .. })
+        ));
+    }
+}
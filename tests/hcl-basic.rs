@@ -0,0 +1,52 @@
+use better_config::{env, HclConfig};
+
+#[env(HclConfig)]
+pub struct AppConfig {
+    #[conf(from = "name")]
+    pub name: String,
+    #[conf(from = "version")]
+    pub version: u32,
+    #[conf(from = "debug", default = "false")]
+    pub debug: bool,
+    #[conf(from = "listener[1].port", default = "0")]
+    pub second_listener: u16,
+    #[env]
+    pub api: ApiUpstream,
+}
+
+#[env(HclConfig(prefix = "upstream.api."))]
+pub struct ApiUpstream {
+    #[conf(from = "host", default = "localhost")]
+    pub host: String,
+    #[conf(from = "port", default = "0")]
+    pub port: u16,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+    use std::env;
+
+    #[test]
+    #[serial]
+    fn labelled_blocks_nest() {
+        env::remove_var("UPSTREAM_API_PORT");
+        let config = AppConfig::builder().build().unwrap();
+        assert_eq!(config.name, "config.hcl");
+        assert_eq!(config.version, 4);
+        assert!(config.debug);
+        assert_eq!(config.second_listener, 8443);
+        assert_eq!(config.api.host, "10.0.0.10");
+        assert_eq!(config.api.port, 8080);
+    }
+
+    #[test]
+    #[serial]
+    fn env_override() {
+        env::set_var("UPSTREAM_API_PORT", "9090");
+        let config = ApiUpstream::builder().build().unwrap();
+        assert_eq!(config.port, 9090);
+        env::remove_var("UPSTREAM_API_PORT");
+    }
+}
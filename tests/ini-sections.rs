@@ -0,0 +1,108 @@
+use better_config::{env, IniConfig};
+use std::collections::HashSet;
+
+#[env(IniConfig(target = "config-sections.ini"))]
+pub struct SectionsConfig {
+    #[conf(from = "server.http.port")]
+    pub http_port: u16,
+    #[conf(from = "server.http.timeout")]
+    pub http_timeout: u32,
+    #[conf(from = "Service.ExecStart[0]")]
+    pub migrate: String,
+    #[conf(from = "Service.ExecStart[1]")]
+    pub exec_start: String,
+    #[env]
+    pub api: Upstream,
+}
+
+#[env(IniConfig(prefix = "upstream.api.", target = "config-sections.ini"))]
+pub struct Upstream {
+    #[conf(from = "host")]
+    pub host: String,
+    #[conf(from = "timeout")]
+    pub timeout: u32,
+}
+
+#[env(IniConfig(target = "gitconfig", git_style))]
+pub struct GitConfig {
+    #[conf(from = "core.bare", default = "false")]
+    pub bare: bool,
+    #[conf(from = "core.filemode", default = "true")]
+    pub file_mode: bool,
+    #[conf(from = "remote.Origin.url")]
+    pub origin_url: String,
+    #[conf(from = "remote.Origin.fetch[1]")]
+    pub tags_refspec: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+    use std::env;
+
+    #[test]
+    #[serial]
+    fn nested_sections_duplicates_and_defaults() {
+        env::remove_var("UPSTREAM_API_TIMEOUT");
+        let config = SectionsConfig::builder().build().unwrap();
+        assert_eq!(config.http_port, 8080);
+        assert_eq!(config.http_timeout, 30);
+        assert_eq!(config.migrate, "/usr/bin/app --migrate");
+        assert_eq!(config.exec_start, "/usr/bin/app");
+        assert_eq!(config.api.host, "10.0.0.10");
+        assert_eq!(config.api.timeout, 5);
+    }
+
+    #[test]
+    #[serial]
+    fn env_override_subsection() {
+        env::set_var("UPSTREAM_API_TIMEOUT", "9");
+        let config = Upstream::builder().build().unwrap();
+        assert_eq!(config.timeout, 9);
+        env::remove_var("UPSTREAM_API_TIMEOUT");
+    }
+
+    #[test]
+    #[serial]
+    fn git_style() {
+        let config = GitConfig::builder().build().unwrap();
+        assert!(config.bare);
+        assert!(!config.file_mode);
+        assert_eq!(
+            config.origin_url,
+            "git@github.com:bingryan/better-config-rs.git"
+        );
+        assert_eq!(config.tags_refspec, "+refs/tags/*:refs/tags/*");
+    }
+
+    #[test]
+    fn repeated_sections_are_merged() {
+        let loaded = <SectionsConfig as IniConfig>::load_from_str_detailed(
+            "[DEFAULT]\ntimeout = 5\n[server]\nhost = a\nhost = b\n[db]\nport = 1\n[server]\nhost = c\nport = 80\n",
+            &HashSet::new(),
+        )
+        .unwrap();
+        assert_eq!(loaded.values["server.host[0]"], "a");
+        assert_eq!(loaded.values["server.host[1]"], "b");
+        assert_eq!(loaded.values["server.host[2]"], "c");
+        assert_eq!(loaded.values["server.port"], "80");
+        assert_eq!(loaded.values["server.timeout"], "5");
+        assert_eq!(loaded.values["db.port"], "1");
+        assert!(!loaded.values.contains_key("server.host"));
+    }
+
+    #[test]
+    #[serial]
+    fn git_style_repeated_sections_and_inline_comments() {
+        let config = GitConfig::builder()
+            .source_str(
+                "[core]\n\tbare # a bare repository\n[remote \"Origin\"]\n\turl = a.git\n\tfetch = heads\n[remote \"Origin\"]\n\tfetch = \"tags ; all\"\n",
+            )
+            .build()
+            .unwrap();
+        assert!(config.bare);
+        assert_eq!(config.origin_url, "a.git");
+        assert_eq!(config.tags_refspec, "tags ; all");
+    }
+}
@@ -0,0 +1,173 @@
+use better_config::{env, TomlConfig};
+
+/// Settings of the web service
+#[env(TomlConfig)]
+pub struct AppConfig {
+    /// Name shown in logs
+    #[conf(from = "name")]
+    pub name: String,
+    #[conf(
+        from = "logging.level",
+        default = "info",
+        one_of("debug", "info", "warn")
+    )]
+    pub level: String,
+    #[conf(from = "logging.sample_rate", default = "0.5", min = "0", max = "1")]
+    pub sample_rate: f64,
+    #[conf(from = "debug")]
+    pub debug: Option<bool>,
+    #[env]
+    pub database: DatabaseConfig,
+}
+
+#[env(TomlConfig(prefix = "database."))]
+pub struct DatabaseConfig {
+    /// Host of the primary,
+    /// without the port
+    #[conf(from = "host")]
+    pub host: String,
+    #[conf(from = "port", default = "5432", min = "1", max = "65535")]
+    pub port: u16,
+    #[conf(from = "url")]
+    pub url: Option<url_like::Url>,
+}
+
+mod url_like {
+    #[derive(Debug, Default)]
+    pub struct Url(pub String);
+
+    impl std::str::FromStr for Url {
+        type Err = String;
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            Ok(Url(s.to_string()))
+        }
+    }
+}
+
+#[env(TomlConfig(prefix = "server."))]
+pub struct PlainFieldConfig {
+    pub host: String,
+}
+
+#[env(TomlConfig)]
+pub struct ServerConfig {
+    #[conf(from = "port", default = "8080", min = "1024")]
+    pub port: u16,
+    #[conf(from = "mode", default = "prod", one_of("dev", "prod"))]
+    pub mode: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use better_config::Error;
+    use serde_json::json;
+
+    #[test]
+    fn test_json_schema() {
+        let schema = AppConfig::json_schema();
+
+        assert_eq!(
+            schema,
+            json!({
+                "$schema": "https://json-schema.org/draft/2020-12/schema",
+                "title": "AppConfig",
+                "description": "Settings of the web service",
+                "type": "object",
+                "required": ["name"],
+                "properties": {
+                    "name": { "type": "string", "description": "Name shown in logs" },
+                    "debug": { "type": "boolean" },
+                    "logging": {
+                        "type": "object",
+                        "properties": {
+                            "level": {
+                                "type": "string",
+                                "default": "info",
+                                "enum": ["debug", "info", "warn"],
+                            },
+                            "sample_rate": {
+                                "type": "number",
+                                "default": 0.5,
+                                "minimum": 0,
+                                "maximum": 1,
+                            },
+                        },
+                    },
+                    "database": {
+                        "type": "object",
+                        "required": ["host"],
+                        "properties": {
+                            "host": {
+                                "type": "string",
+                                "description": "Host of the primary,\nwithout the port",
+                            },
+                            "port": {
+                                "type": "integer",
+                                "default": 5432,
+                                "minimum": 1,
+                                "maximum": 65535,
+                            },
+                            "url": {},
+                        },
+                    },
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn test_config_fields() {
+        let fields = AppConfig::config_fields();
+        let keys: Vec<_> = fields.iter().map(|f| f.key).collect();
+        assert_eq!(
+            keys,
+            [
+                "name",
+                "logging.level",
+                "logging.sample_rate",
+                "debug",
+                "database.host",
+                "database.port",
+                "database.url"
+            ]
+        );
+        assert_eq!(fields[3].type_name, "bool");
+        assert_eq!(fields[6].type_name, "url_like::Url");
+    }
+
+    #[test]
+    fn test_field_without_conf_is_left_out() {
+        assert!(PlainFieldConfig::config_fields().is_empty());
+        assert_eq!(PlainFieldConfig::json_schema()["properties"], json!({}));
+    }
+
+    #[test]
+    fn test_constraints_are_checked() {
+        let config = ServerConfig::builder()
+            .source_str("port = 8443\nmode = \"dev\"\n")
+            .build()
+            .unwrap();
+        assert_eq!(config.port, 8443);
+
+        let err = ServerConfig::builder()
+            .source_str("port = 80\n")
+            .build()
+            .err()
+            .unwrap();
+        assert!(matches!(err, Error::ValueError { ref key, .. } if key == "port"));
+        assert!(err
+            .to_string()
+            .contains("expected a value >= 1024, got '80'"));
+
+        let err = ServerConfig::builder()
+            .source_str("mode = \"staging\"\n")
+            .build()
+            .err()
+            .unwrap();
+        assert!(err
+            .to_string()
+            .contains("expected one of 'dev', 'prod', got 'staging'"));
+    }
+}
@@ -0,0 +1,40 @@
+use better_config::{env, Json5Config};
+
+#[env(Json5Config)]
+pub struct AppConfig {
+    #[conf(from = "name")]
+    pub name: String,
+    #[conf(from = "version")]
+    pub version: u32,
+    #[conf(from = "database.host", default = "localhost")]
+    pub db_host: String,
+    #[conf(from = "database.ports[1]", default = "0")]
+    pub replica_port: u16,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+    use std::env;
+
+    #[test]
+    #[serial]
+    fn basic_defaults() {
+        env::remove_var("DATABASE_HOST");
+        let config = AppConfig::builder().build().unwrap();
+        assert_eq!(config.name, "config.json5");
+        assert_eq!(config.version, 2);
+        assert_eq!(config.db_host, "127.0.0.1");
+        assert_eq!(config.replica_port, 5433);
+    }
+
+    #[test]
+    #[serial]
+    fn env_override() {
+        env::set_var("DATABASE_HOST", "db.internal");
+        let config = AppConfig::builder().build().unwrap();
+        assert_eq!(config.db_host, "db.internal");
+        env::remove_var("DATABASE_HOST");
+    }
+}
@@ -0,0 +1,58 @@
+use better_config::{env, KdlConfig};
+
+#[env(KdlConfig)]
+pub struct AppConfig {
+    #[conf(from = "name")]
+    pub name: String,
+    #[conf(from = "version")]
+    pub version: u32,
+    #[conf(from = "debug", default = "false")]
+    pub debug: bool,
+    #[conf(from = "listener[1].port", default = "0")]
+    pub tls_port: u16,
+    #[conf(from = "listener[1].tls", default = "false")]
+    pub tls: bool,
+    #[env]
+    pub database: Database,
+}
+
+#[env(KdlConfig(prefix = "database."))]
+pub struct Database {
+    #[conf(from = "host", default = "localhost")]
+    pub host: String,
+    #[conf(from = "port", default = "0")]
+    pub port: u16,
+    #[conf(from = "replicas[1]", default = "")]
+    pub second_replica: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+    use std::env;
+
+    #[test]
+    #[serial]
+    fn nodes_arguments_and_properties() {
+        env::remove_var("DATABASE_PORT");
+        let config = AppConfig::builder().build().unwrap();
+        assert_eq!(config.name, "config.kdl");
+        assert_eq!(config.version, 7);
+        assert!(config.debug);
+        assert_eq!(config.tls_port, 443);
+        assert!(config.tls);
+        assert_eq!(config.database.host, "127.0.0.1");
+        assert_eq!(config.database.port, 5432);
+        assert_eq!(config.database.second_replica, "10.0.0.3");
+    }
+
+    #[test]
+    #[serial]
+    fn env_override() {
+        env::set_var("DATABASE_PORT", "6432");
+        let config = Database::builder().build().unwrap();
+        assert_eq!(config.port, 6432);
+        env::remove_var("DATABASE_PORT");
+    }
+}
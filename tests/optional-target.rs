@@ -0,0 +1,65 @@
+use better_config::{env, EnvConfig, JsonConfig, TomlConfig};
+
+#[env(TomlConfig(target = "config.toml,config.local.toml?"))]
+pub struct TomlAppConfig {
+    #[conf(from = "title", default = "hello toml")]
+    pub title: String,
+}
+
+#[env(JsonConfig(target = "config.json?"))]
+pub struct JsonAppConfig {
+    #[conf(from = "name", default = "default-name")]
+    pub name: String,
+}
+
+#[env(JsonConfig(target = "config.missing.json"))]
+pub struct RequiredAppConfig {
+    #[conf(from = "name", default = "default-name")]
+    pub name: String,
+}
+
+#[env(EnvConfig(prefix = "BETTER_", target = ".env.local?,.env.dev"))]
+pub struct EnvAppConfig {
+    #[conf(from = "DB_HOST", default = "localhost")]
+    pub host: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+    use std::env;
+
+    #[test]
+    #[serial]
+    fn missing_optional_file_is_skipped() {
+        let config = TomlAppConfig::builder().build().unwrap();
+        assert_eq!(config.title, "TOML Example");
+    }
+
+    #[test]
+    #[serial]
+    fn existing_optional_file_is_loaded() {
+        let config = JsonAppConfig::builder().build().unwrap();
+        assert_eq!(config.name, "config.json");
+    }
+
+    #[test]
+    #[serial]
+    fn missing_required_file_fails() {
+        let result = RequiredAppConfig::builder().build();
+        assert!(matches!(
+            result,
+            Err(better_config::Error::LoadFileError { .. })
+        ));
+    }
+
+    #[test]
+    #[serial]
+    fn missing_optional_env_file_is_skipped() {
+        env::remove_var("BETTER_DB_HOST");
+        let config = EnvAppConfig::builder().build().unwrap();
+        assert_eq!(config.host, "dev");
+        env::remove_var("BETTER_DB_HOST");
+    }
+}
@@ -0,0 +1,55 @@
+use better_config::{env, Error, TomlConfig};
+
+#[env(TomlConfig(target = "conf.d/../config.toml"))]
+pub struct TraversalAppConfig {
+    #[conf(from = "title", default = "hello toml")]
+    pub title: String,
+}
+
+#[env(TomlConfig(target = "conf.d/../config.toml", sandbox_root = "conf.d"))]
+pub struct SandboxedTraversalAppConfig {
+    #[conf(from = "title", default = "hello toml")]
+    pub title: String,
+}
+
+#[env(TomlConfig(target = "conf.d/*.toml", sandbox_root = "conf.d"))]
+pub struct SandboxedAppConfig {
+    #[conf(from = "database.host", default = "localhost")]
+    pub host: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+    use std::env;
+
+    #[test]
+    fn permissive_by_default() {
+        let config = TraversalAppConfig::builder().build().unwrap();
+        assert_eq!(config.title, "TOML Example");
+    }
+
+    #[test]
+    fn sandbox_rejects_paths_outside_root() {
+        let result = SandboxedTraversalAppConfig::builder().build();
+        assert!(matches!(result, Err(Error::InvalidPathError { .. })));
+
+        // the sandbox of one struct does not restrict the others
+        assert!(TraversalAppConfig::builder().build().is_ok());
+    }
+
+    #[test]
+    #[serial]
+    fn sandbox_accepts_paths_inside_root() {
+        env::remove_var("DATABASE_HOST");
+        let result = SandboxedAppConfig::builder().build();
+        assert_eq!(result.unwrap().host, "127.0.0.1");
+    }
+
+    #[test]
+    fn runtime_target_is_checked_against_the_sandbox() {
+        let result = SandboxedAppConfig::builder().target("config.toml").build();
+        assert!(matches!(result, Err(Error::InvalidPathError { .. })));
+    }
+}
@@ -0,0 +1,88 @@
+use better_config::{env, PropertiesConfig};
+
+#[env(PropertiesConfig)]
+pub struct AppConfig {
+    #[conf(from = "app.name")]
+    pub name: String,
+    #[conf(from = "app.version")]
+    pub version: u32,
+    #[conf(from = "app.debug", default = "false")]
+    pub debug: bool,
+    #[conf(from = "greeting", default = "")]
+    pub greeting: String,
+    #[conf(from = "path with spaces", default = "")]
+    pub path: String,
+    #[env]
+    pub datasource: DataSource,
+}
+
+#[env(PropertiesConfig(prefix = "spring.datasource."))]
+pub struct DataSource {
+    #[conf(from = "url")]
+    pub url: String,
+    #[conf(from = "username")]
+    pub username: String,
+    #[conf(from = "password")]
+    pub password: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use better_config::Format;
+    use serial_test::serial;
+    use std::env;
+
+    #[test]
+    #[serial]
+    fn basic_defaults() {
+        env::remove_var("SPRING_DATASOURCE_USERNAME");
+        let config = AppConfig::builder().build().unwrap();
+        assert_eq!(config.name, "application.properties");
+        assert_eq!(config.version, 5);
+        assert!(config.debug);
+        assert_eq!(config.greeting, "Hello\tWorld!");
+        assert_eq!(config.path, "C:\\data");
+        assert_eq!(
+            config.datasource.url,
+            "jdbc:postgresql://localhost:5432/app"
+        );
+        assert_eq!(config.datasource.username, "admin");
+        assert_eq!(config.datasource.password, "s3cret");
+    }
+
+    #[test]
+    #[serial]
+    fn env_override() {
+        env::set_var("SPRING_DATASOURCE_USERNAME", "operator");
+        let config = DataSource::builder().build().unwrap();
+        assert_eq!(config.username, "operator");
+        env::remove_var("SPRING_DATASOURCE_USERNAME");
+    }
+
+    #[test]
+    fn separators_and_escapes() {
+        let content =
+            "a=1\nb:2\nc 3\nd = = 4\ne\\:f = 5\nempty\nemoji = \\uD83D\\uDE00\nlast = odd\\\\\n";
+        let map = better_config::parse_str(content, Format::Properties)
+            .unwrap()
+            .values;
+        assert_eq!(map["a"], "1");
+        assert_eq!(map["b"], "2");
+        assert_eq!(map["c"], "3");
+        assert_eq!(map["d"], "= 4");
+        assert_eq!(map["e:f"], "5");
+        assert_eq!(map["empty"], "");
+        assert_eq!(map["emoji"], "😀");
+        assert_eq!(map["last"], "odd\\");
+    }
+
+    #[test]
+    fn malformed_unicode_escape() {
+        let result = better_config::parse_str("key = \\u12G4", Format::Properties);
+        assert!(matches!(
+            result,
+            Err(better_config::Error::ParseError { .. })
+        ));
+    }
+}
@@ -0,0 +1,52 @@
+use better_config::{env, RonConfig};
+
+#[env(RonConfig)]
+pub struct AppConfig {
+    #[conf(from = "name")]
+    pub name: String,
+    #[conf(from = "version")]
+    pub version: u32,
+    #[conf(from = "debug", default = "false")]
+    pub debug: bool,
+    #[conf(from = "database.host", default = "localhost")]
+    pub db_host: String,
+    #[conf(from = "database.ports[1]", default = "0")]
+    pub replica_port: u16,
+    #[conf(from = "database.replica", default = "none")]
+    pub replica: String,
+    #[conf(from = "labels.team", default = "none", no_env_override)]
+    pub team: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+    use std::env;
+
+    #[test]
+    #[serial]
+    fn basic_defaults() {
+        env::remove_var("DATABASE_HOST");
+        let config = AppConfig::builder().build().unwrap();
+        assert_eq!(config.name, "config.ron");
+        assert_eq!(config.version, 3);
+        assert!(config.debug);
+        assert_eq!(config.db_host, "127.0.0.1");
+        assert_eq!(config.replica_port, 5433);
+        assert_eq!(config.replica, "10.0.0.2");
+        assert_eq!(config.team, "platform");
+    }
+
+    #[test]
+    #[serial]
+    fn env_override_and_excluded_keys() {
+        env::set_var("DATABASE_HOST", "db.internal");
+        env::set_var("LABELS_TEAM", "other");
+        let config = AppConfig::builder().build().unwrap();
+        assert_eq!(config.db_host, "db.internal");
+        assert_eq!(config.team, "platform");
+        env::remove_var("DATABASE_HOST");
+        env::remove_var("LABELS_TEAM");
+    }
+}
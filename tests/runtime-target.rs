@@ -0,0 +1,143 @@
+use better_config::{env, AbstractConfig, JsonConfig, TomlConfig};
+
+#[env(JsonConfig(target = "config.missing.json", target_env = "BETTER_CONFIG_TARGET"))]
+pub struct AppConfig {
+    #[conf(from = "name", default = "default-name")]
+    pub name: String,
+    #[conf(from = "debug", default = "false")]
+    pub debug: bool,
+}
+
+#[env(JsonConfig(target = "config.json"))]
+pub struct ParentConfig {
+    #[conf(from = "name", default = "default-name")]
+    pub name: String,
+    #[env]
+    pub database: DatabaseConfig,
+}
+
+#[env(JsonConfig(prefix = "database.", target = "config.missing.json"))]
+pub struct DatabaseConfig {
+    #[conf(from = "host", default = "localhost")]
+    pub host: String,
+}
+
+#[env(JsonConfig(target = "config.json"))]
+pub struct ServiceConfig {
+    #[conf(from = "name", default = "default-name")]
+    pub name: String,
+    #[env]
+    pub database: InheritingDatabaseConfig,
+    #[env]
+    pub pinned: PinnedDatabaseConfig,
+    #[env]
+    pub toml: TomlDatabaseConfig,
+}
+
+#[env(JsonConfig(prefix = "database."))]
+pub struct InheritingDatabaseConfig {
+    #[conf(from = "host", default = "localhost")]
+    pub host: String,
+}
+
+#[env(JsonConfig(prefix = "database.", target = "config.json"))]
+pub struct PinnedDatabaseConfig {
+    #[conf(from = "host", default = "localhost")]
+    pub host: String,
+}
+
+#[env(TomlConfig(prefix = "database."))]
+pub struct TomlDatabaseConfig {
+    #[conf(from = "enabled", default = "false")]
+    pub enabled: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+    use std::env;
+
+    fn fixture() {
+        env::remove_var("BETTER_CONFIG_TARGET");
+        env::remove_var("NAME");
+        env::remove_var("DEBUG");
+    }
+
+    #[test]
+    #[serial]
+    fn compile_time_target_is_used_by_default() {
+        fixture();
+        let result = AppConfig::builder().build();
+        assert!(matches!(
+            result,
+            Err(better_config::Error::LoadFileError { .. })
+        ));
+    }
+
+    #[test]
+    #[serial]
+    fn target_env_overrides_compile_time_target() {
+        fixture();
+        env::set_var("BETTER_CONFIG_TARGET", "config.json");
+        let config = AppConfig::builder().build().unwrap();
+        assert_eq!(config.name, "config.json");
+        fixture();
+    }
+
+    #[test]
+    #[serial]
+    fn builder_target_overrides_target_env() {
+        fixture();
+        env::set_var("BETTER_CONFIG_TARGET", "config.json");
+        let config = AppConfig::builder()
+            .target("config-nested.json")
+            .build()
+            .unwrap();
+        assert_eq!(config.name, "config-nested.json");
+        assert!(config.debug);
+        fixture();
+    }
+
+    #[test]
+    #[serial]
+    fn abstract_config_load_uses_given_target() {
+        fixture();
+        let params = <AppConfig as AbstractConfig>::load(Some("config.json".to_string())).unwrap();
+        assert_eq!(params.get("name").map(String::as_str), Some("config.json"));
+
+        let result = <AppConfig as AbstractConfig>::load(None);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    #[serial]
+    fn nested_failure_is_returned() {
+        fixture();
+        let result = ParentConfig::builder().build();
+        assert!(matches!(
+            result,
+            Err(better_config::Error::LoadFileError { .. })
+        ));
+    }
+
+    #[test]
+    #[serial]
+    fn builder_target_is_passed_to_nested_configs_of_the_same_loader() {
+        fixture();
+        let config = ServiceConfig::builder().build().unwrap();
+        assert_eq!(config.name, "config.json");
+        assert_eq!(config.database.host, "localhost");
+
+        let config = ServiceConfig::builder()
+            .target("config-nested.json")
+            .build()
+            .unwrap();
+        assert_eq!(config.name, "config-nested.json");
+        assert_eq!(config.database.host, "127.0.0.1");
+        // a nested config with its own target keeps it
+        assert_eq!(config.pinned.host, "localhost");
+        // a nested config of another loader keeps its default target
+        assert!(config.toml.enabled);
+    }
+}
@@ -0,0 +1,106 @@
+use better_config::{env, Format, SampleFormat, TomlConfig};
+
+#[env(TomlConfig)]
+pub struct AppConfig {
+    /// Name shown in logs
+    #[conf(from = "name", default = "my app")]
+    pub name: String,
+    #[conf(from = "debug")]
+    pub debug: Option<bool>,
+    #[env]
+    pub database: DatabaseConfig,
+}
+
+#[env(TomlConfig(prefix = "database."))]
+pub struct DatabaseConfig {
+    /// Host of the primary
+    #[conf(from = "host")]
+    pub host: String,
+    #[conf(from = "port", default = "5432")]
+    pub port: u16,
+    #[conf(from = "password", default = "postgres", secret)]
+    pub password: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_toml_sample() {
+        assert_eq!(
+            AppConfig::sample(SampleFormat::Toml),
+            r#"# Name shown in logs
+name = "my app"
+# debug = false
+
+[database]
+# Host of the primary
+host = ""
+port = 5432
+password = "<secret>"
+"#
+        );
+    }
+
+    #[test]
+    fn test_yml_sample() {
+        assert_eq!(
+            AppConfig::sample(SampleFormat::Yml),
+            r#"# Name shown in logs
+name: "my app"
+# debug: false
+database:
+  # Host of the primary
+  host: ""
+  port: 5432
+  password: "<secret>"
+"#
+        );
+    }
+
+    #[test]
+    fn test_samples_load_back() {
+        for (sample_format, format) in [
+            (SampleFormat::Toml, Format::Toml),
+            (SampleFormat::Json, Format::Json),
+            (SampleFormat::Yml, Format::Yml),
+            (SampleFormat::Ini, Format::Ini),
+        ] {
+            let sample = AppConfig::sample(sample_format);
+            let params = better_config::parse_str(&sample, format).unwrap_or_else(|e| {
+                panic!("{:?} sample does not parse: {}\n{}", format, e, sample)
+            });
+            let params = params.values;
+
+            assert_eq!(params["name"], "my app", "{:?}", format);
+            assert_eq!(params["database.host"], "", "{:?}", format);
+            assert_eq!(params["database.port"], "5432", "{:?}", format);
+            assert_eq!(params["database.password"], "<secret>", "{:?}", format);
+            assert!(!params.contains_key("debug"), "{:?}", format);
+        }
+    }
+
+    #[test]
+    fn test_env_sample_names_variables() {
+        assert_eq!(
+            AppConfig::sample(SampleFormat::Env),
+            r#"# Name shown in logs
+NAME='my app'
+# DEBUG=false
+# Host of the primary
+DATABASE_HOST=''
+DATABASE_PORT=5432
+DATABASE_PASSWORD='<secret>'
+"#
+        );
+    }
+
+    #[test]
+    fn test_secret_default_not_in_schema() {
+        let schema = AppConfig::json_schema();
+        let password = &schema["properties"]["database"]["properties"]["password"];
+        assert!(password.get("default").is_none());
+        assert_eq!(password["writeOnly"], true);
+    }
+}
@@ -0,0 +1,133 @@
+use better_config::{env, TomlConfig};
+
+#[env(TomlConfig(search = "better-config"))]
+pub struct FirstMatchConfig {
+    #[conf(from = "title", default = "hello toml")]
+    pub title: String,
+    #[conf(from = "xdg.enabled", default = "false")]
+    pub xdg_enabled: bool,
+}
+
+#[env(TomlConfig(search = "better-config", layered))]
+pub struct LayeredConfig {
+    #[conf(from = "title", default = "hello toml")]
+    pub title: String,
+    #[conf(from = "xdg.enabled", default = "false")]
+    pub xdg_enabled: bool,
+}
+
+#[env(TomlConfig(search = "better-config", target = "settings.toml"))]
+pub struct NamedFileConfig {
+    #[conf(from = "name", default = "default-name")]
+    pub name: String,
+}
+
+#[env(TomlConfig(search = "better-config", target = "missing.toml?,settings.toml"))]
+pub struct SeveralFilesConfig {
+    #[conf(from = "name", default = "default-name")]
+    pub name: String,
+}
+
+#[env(TomlConfig(search = "better-config", target = "search.d/*.toml"))]
+pub struct GlobConfig {
+    #[conf(from = "name", default = "default-name")]
+    pub name: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+    use std::env;
+    use std::ffi::OsString;
+    use std::fs;
+    use std::path::PathBuf;
+
+    /// Points `XDG_CONFIG_HOME` and `HOME` at a temporary directory until the test ends
+    struct ConfigHome {
+        dir: PathBuf,
+        previous: Vec<(&'static str, Option<OsString>)>,
+    }
+
+    impl Drop for ConfigHome {
+        fn drop(&mut self) {
+            for (var, value) in self.previous.drain(..) {
+                match value {
+                    Some(value) => env::set_var(var, value),
+                    None => env::remove_var(var),
+                }
+            }
+            let _ = fs::remove_dir_all(&self.dir);
+        }
+    }
+
+    fn fixture() -> ConfigHome {
+        env::remove_var("TITLE");
+        env::remove_var("XDG_ENABLED");
+        env::remove_var("NAME");
+
+        let dir = env::temp_dir().join(format!("better-config-search-{}", std::process::id()));
+        let app_dir = dir.join("xdg").join("better-config");
+        fs::create_dir_all(app_dir.join("search.d")).unwrap();
+        fs::create_dir_all(dir.join("home")).unwrap();
+        fs::write(
+            app_dir.join("config.toml"),
+            "title = \"XDG Example\"\n\n[xdg]\nenabled = true\n",
+        )
+        .unwrap();
+        fs::write(app_dir.join("settings.toml"), "name = \"xdg settings\"\n").unwrap();
+        fs::write(app_dir.join("search.d/10-name.toml"), "name = \"first\"\n").unwrap();
+        fs::write(app_dir.join("search.d/20-name.toml"), "name = \"second\"\n").unwrap();
+
+        let previous = ["XDG_CONFIG_HOME", "HOME"]
+            .into_iter()
+            .map(|var| (var, env::var_os(var)))
+            .collect();
+        env::set_var("XDG_CONFIG_HOME", dir.join("xdg"));
+        env::set_var("HOME", dir.join("home"));
+        ConfigHome { dir, previous }
+    }
+
+    #[test]
+    #[serial]
+    fn first_match_prefers_current_dir() {
+        let _home = fixture();
+        let config = FirstMatchConfig::builder().build().unwrap();
+        assert_eq!(config.title, "TOML Example");
+        assert!(!config.xdg_enabled);
+    }
+
+    #[test]
+    #[serial]
+    fn layered_merges_all_matches() {
+        let _home = fixture();
+        let config = LayeredConfig::builder().build().unwrap();
+        // ./config.toml is more specific than $XDG_CONFIG_HOME/better-config/config.toml
+        assert_eq!(config.title, "TOML Example");
+        assert!(config.xdg_enabled);
+    }
+
+    #[test]
+    #[serial]
+    fn search_uses_target_as_file_name() {
+        let _home = fixture();
+        let config = NamedFileConfig::builder().build().unwrap();
+        assert_eq!(config.name, "xdg settings");
+    }
+
+    #[test]
+    #[serial]
+    fn search_looks_for_each_file_of_the_target() {
+        let _home = fixture();
+        let config = SeveralFilesConfig::builder().build().unwrap();
+        assert_eq!(config.name, "xdg settings");
+    }
+
+    #[test]
+    #[serial]
+    fn search_expands_glob_targets() {
+        let _home = fixture();
+        let config = GlobConfig::builder().build().unwrap();
+        assert_eq!(config.name, "second");
+    }
+}
@@ -0,0 +1,107 @@
+use better_config::{env, Format, TomlConfig, YmlConfig};
+
+#[env(TomlConfig(target = "config.missing.toml"))]
+pub struct AppConfig {
+    #[conf(from = "title", default = "default-title")]
+    pub title: String,
+    #[conf(from = "server.port", default = "80")]
+    pub port: u16,
+}
+
+#[env(YmlConfig)]
+pub struct YmlAppConfig {
+    #[conf(from = "name", default = "default-name")]
+    pub name: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+    use std::collections::HashMap;
+    use std::env;
+
+    const EMBEDDED: &str = r#"
+title = "embedded"
+
+[server]
+port = 8080
+"#;
+
+    fn fixture() {
+        env::remove_var("TITLE");
+        env::remove_var("SERVER_PORT");
+    }
+
+    #[test]
+    #[serial]
+    fn builder_source_str_replaces_target() {
+        fixture();
+        let config = AppConfig::builder().source_str(EMBEDDED).build().unwrap();
+        assert_eq!(config.title, "embedded");
+        assert_eq!(config.port, 8080);
+    }
+
+    #[test]
+    #[serial]
+    fn source_str_keeps_env_override() {
+        fixture();
+        env::set_var("SERVER_PORT", "9090");
+        let config = AppConfig::builder().source_str(EMBEDDED).build().unwrap();
+        assert_eq!(config.port, 9090);
+        fixture();
+    }
+
+    #[test]
+    #[serial]
+    fn invalid_source_str_is_a_parse_error() {
+        fixture();
+        let result = AppConfig::builder().source_str("title = ").build();
+        assert!(matches!(
+            result,
+            Err(better_config::Error::ParseError { .. })
+        ));
+    }
+
+    #[test]
+    #[serial]
+    fn load_from_reader() {
+        fixture();
+        let params: HashMap<String, String> =
+            <AppConfig as TomlConfig>::load_from_reader(EMBEDDED.as_bytes()).unwrap();
+        assert_eq!(params.get("server.port").map(String::as_str), Some("8080"));
+    }
+
+    #[test]
+    #[serial]
+    fn yml_load_from_str() {
+        env::remove_var("NAME");
+        let config = YmlAppConfig::builder()
+            .source_str("name: inline")
+            .build()
+            .unwrap();
+        assert_eq!(config.name, "inline");
+    }
+
+    #[test]
+    fn parse_str_by_format() {
+        let map = better_config::parse_str(r#"{"server": {"ports": [80, 443]}}"#, Format::Json)
+            .unwrap()
+            .values;
+        assert_eq!(map.get("server.ports[1]").map(String::as_str), Some("443"));
+
+        let map = better_config::parse_str("[server]\nhost = localhost", Format::Ini)
+            .unwrap()
+            .values;
+        assert_eq!(
+            map.get("server.host").map(String::as_str),
+            Some("localhost")
+        );
+
+        let map = better_config::parse_str("API_KEY=secret\n", Format::Env)
+            .unwrap()
+            .values;
+        assert_eq!(map.get("API_KEY").map(String::as_str), Some("secret"));
+        assert!(env::var("API_KEY").is_err());
+    }
+}
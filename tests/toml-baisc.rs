@@ -9,8 +9,16 @@ pub struct AppConfig {
     #[conf(from = "database.enabled", default = "false")]
     pub database_enabled: bool,
 
-    #[conf(from = "database.ports")]
-    pub database_ports: String,
+    #[conf(from = "database.ports[1]")]
+    pub database_port: u16,
+    #[conf(from = "database.data[0][1]")]
+    pub database_data: String,
+    #[conf(from = "database.temp_targets.cpu")]
+    pub cpu_target: f64,
+    #[conf(from = "owner.dob")]
+    pub owner_dob: String,
+    #[conf(from = "products[1].name")]
+    pub second_product: String,
 }
 
 #[cfg(test)]
@@ -31,6 +39,22 @@ mod tests {
         let config = AppConfig::builder().build().unwrap();
         assert_eq!(config.title, "TOML Example");
         assert!(config.database_enabled);
-        assert_eq!(config.database_ports, "[8000, 8001, 8002]");
+        assert_eq!(config.database_port, 8001);
+        assert_eq!(config.database_data, "phi");
+        assert_eq!(config.cpu_target, 79.5);
+    }
+
+    #[test]
+    #[serial]
+    fn datetimes_and_arrays_of_tables() {
+        let config = AppConfig::builder().build().unwrap();
+        assert_eq!(config.owner_dob, "1979-05-27T07:32:00-08:00");
+        assert_eq!(config.second_product, "Nail");
+
+        let map =
+            better_config::parse_str("t = 1979-05-27 07:32:00.5z", better_config::Format::Toml)
+                .unwrap()
+                .values;
+        assert_eq!(map["t"], "1979-05-27T07:32:00.5Z");
     }
 }
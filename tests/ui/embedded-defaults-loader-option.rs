@@ -0,0 +1,9 @@
+use better_config::env;
+
+#[env(better_config::IniConfig(git_style, embedded_defaults = "defaults.ini"))]
+pub struct AppConfig {
+    #[conf(from = "server.port")]
+    pub port: u16,
+}
+
+fn main() {}
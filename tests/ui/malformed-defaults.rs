@@ -0,0 +1,10 @@
+use better_config::env;
+
+// trybuild compiles this file in target/tests/trybuild/better-config
+#[env(better_config::TomlConfig(embedded_defaults = "../../../../tests/ui/malformed-defaults.toml"))]
+pub struct AppConfig {
+    #[conf(from = "server.port")]
+    pub port: u16,
+}
+
+fn main() {}
@@ -0,0 +1,15 @@
+use better_config::env;
+
+#[env(better_config::EnvConfig(target = ".env", expnad = false))]
+pub struct AppConfig {
+    #[conf(from = "NAME", default = "app")]
+    pub name: String,
+}
+
+#[env(better_config::XmlConfig(attr_prefix))]
+pub struct XmlAppConfig {
+    #[conf(from = "name", default = "app")]
+    pub name: String,
+}
+
+fn main() {}
@@ -0,0 +1,178 @@
+use better_config::{env, EnvConfig, TomlConfig};
+
+#[env(TomlConfig(target = "config-typo.toml", deny_unknown_keys))]
+pub struct DenyConfig {
+    #[conf(from = "database.host")]
+    pub host: String,
+    #[conf(from = "database.port", default = "5432")]
+    pub port: u16,
+}
+
+#[env(TomlConfig(prefix = "database.", target = "config-typo.toml", deny_unknown_keys))]
+pub struct DatabaseConfig {
+    #[conf(from = "host")]
+    pub host: String,
+    #[conf(from = "port", default = "5432")]
+    pub port: u16,
+}
+
+#[env(TomlConfig(target = "config-typo.toml", deny_unknown_keys))]
+pub struct NestedConfig {
+    #[conf(from = "logging.level", default = "info")]
+    pub level: String,
+    #[env]
+    pub database: DatabaseConfig,
+}
+
+#[env(TomlConfig(target = "config-typo.toml", warn_unknown_keys))]
+pub struct WarnConfig {
+    #[conf(from = "database.host")]
+    pub host: String,
+}
+
+#[env(EnvConfig(prefix = "UNKNOWN_", deny_unknown_keys))]
+pub struct EnvAppConfig {
+    #[conf(from = "PORT", default = "8080")]
+    pub port: u16,
+}
+
+#[env(EnvConfig(prefix = "PLAIN_", deny_unknown_keys))]
+pub struct PlainFieldConfig {
+    pub plain_name: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use better_config::Error;
+    use serial_test::serial;
+    use std::env;
+
+    fn unknown(err: Error) -> Vec<(String, Option<String>)> {
+        match err {
+            Error::UnknownKeysError { keys } => {
+                keys.into_iter().map(|k| (k.key, k.suggestion)).collect()
+            }
+            other => panic!("unexpected error: {}", other),
+        }
+    }
+
+    #[test]
+    fn known_keys() {
+        assert_eq!(DenyConfig::known_keys(), ["database.host", "database.port"]);
+        assert_eq!(
+            NestedConfig::known_keys(),
+            ["logging.level", "database.host", "database.port"]
+        );
+    }
+
+    #[test]
+    fn deny_with_suggestions() {
+        let err = DenyConfig::builder().build().err().unwrap();
+
+        let location = err.location().unwrap();
+        assert_eq!(
+            (location.name.as_str(), location.line),
+            ("config-typo.toml", 3)
+        );
+        assert!(err
+            .to_string()
+            .contains("unknown key 'databse.host', did you mean 'database.host'?"));
+
+        assert_eq!(
+            unknown(err),
+            [
+                (
+                    "database.prot".to_string(),
+                    Some("database.port".to_string())
+                ),
+                (
+                    "databse.host".to_string(),
+                    Some("database.host".to_string())
+                ),
+                ("logging.level".to_string(), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn deny_under_prefix() {
+        let err = DatabaseConfig::builder().build().err().unwrap();
+        assert_eq!(
+            unknown(err),
+            [(
+                "database.prot".to_string(),
+                Some("database.port".to_string())
+            )]
+        );
+    }
+
+    #[test]
+    fn nested_keys_are_known() {
+        let err = NestedConfig::builder().build().err().unwrap();
+        let keys: Vec<_> = unknown(err).into_iter().map(|(k, _)| k).collect();
+        assert_eq!(keys, ["database.prot", "databse.host"]);
+    }
+
+    #[test]
+    fn warn_keeps_loading() {
+        let config = WarnConfig::builder().build().unwrap();
+        assert_eq!(config.host, "db.internal");
+    }
+
+    // without the `log` feature warnings go to stderr, checked in a child process as the
+    // test harness captures the output of the tests
+    #[test]
+    #[cfg(not(feature = "log"))]
+    fn warn_prints_to_stderr_by_default() {
+        if env::var_os("BETTER_CONFIG_WARN_CHILD").is_some() {
+            WarnConfig::builder().build().unwrap();
+            return;
+        }
+
+        let output = std::process::Command::new(env::current_exe().unwrap())
+            .args([
+                "--exact",
+                "tests::warn_prints_to_stderr_by_default",
+                "--nocapture",
+            ])
+            .env("BETTER_CONFIG_WARN_CHILD", "1")
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(
+            stderr.contains("warning: config-typo.toml:3:8: unknown key 'database.prot'"),
+            "{}",
+            stderr
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn deny_env_vars_under_prefix() {
+        env::set_var("UNKNOWN_PROT", "9090");
+
+        let err = EnvAppConfig::builder().build().err().unwrap();
+        assert_eq!(
+            unknown(err),
+            [("UNKNOWN_PROT".to_string(), Some("UNKNOWN_PORT".to_string()))]
+        );
+
+        env::remove_var("UNKNOWN_PROT");
+        let config = EnvAppConfig::builder().build().unwrap();
+        assert_eq!(config.port, 8080);
+    }
+
+    #[test]
+    #[serial]
+    fn field_without_conf_is_read_from_its_env_var() {
+        assert_eq!(PlainFieldConfig::known_keys(), ["PLAIN_NAME"]);
+        assert!(PlainFieldConfig::config_fields().is_empty());
+
+        env::set_var("PLAIN_NAME", "app");
+        let config = PlainFieldConfig::builder().build().unwrap();
+        assert_eq!(config.plain_name, "app");
+        env::remove_var("PLAIN_NAME");
+    }
+}
@@ -0,0 +1,81 @@
+use better_config::{env, XmlConfig};
+
+#[env(XmlConfig)]
+pub struct AppConfig {
+    #[conf(from = "@version")]
+    pub version: u32,
+    #[conf(from = "name")]
+    pub name: String,
+    #[conf(from = "debug", default = "false")]
+    pub debug: bool,
+    #[conf(from = "server.listen[1]", default = "0")]
+    pub tls_port: u16,
+    #[env]
+    pub database: Database,
+}
+
+#[env(XmlConfig(prefix = "database."))]
+pub struct Database {
+    #[conf(from = "@host", default = "localhost")]
+    pub host: String,
+    #[conf(from = "port", default = "0")]
+    pub port: u16,
+    #[conf(from = "port.@protocol", default = "udp")]
+    pub protocol: String,
+    #[conf(from = "user")]
+    pub user: String,
+}
+
+#[env(XmlConfig(attr_prefix = "_"))]
+pub struct UnderscoreConfig {
+    #[conf(from = "database._host", default = "localhost")]
+    pub host: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use better_config::Format;
+    use serial_test::serial;
+    use std::env;
+
+    #[test]
+    #[serial]
+    fn elements_attributes_and_repeats() {
+        env::remove_var("DATABASE_PORT");
+        let config = AppConfig::builder().build().unwrap();
+        assert_eq!(config.version, 6);
+        assert_eq!(config.name, "config.xml");
+        assert!(config.debug);
+        assert_eq!(config.tls_port, 443);
+        assert_eq!(config.database.host, "127.0.0.1");
+        assert_eq!(config.database.port, 5432);
+        assert_eq!(config.database.protocol, "tcp");
+        assert_eq!(config.database.user, "admin & operator");
+    }
+
+    #[test]
+    #[serial]
+    fn env_override() {
+        env::set_var("DATABASE_PORT", "6432");
+        let config = Database::builder().build().unwrap();
+        assert_eq!(config.port, 6432);
+        env::remove_var("DATABASE_PORT");
+    }
+
+    #[test]
+    #[serial]
+    fn configurable_attr_prefix() {
+        let config = UnderscoreConfig::builder().build().unwrap();
+        assert_eq!(config.host, "127.0.0.1");
+    }
+
+    #[test]
+    fn malformed_xml() {
+        let result = better_config::parse_str("<config><name></config>", Format::Xml);
+        assert!(matches!(
+            result,
+            Err(better_config::Error::ParseError { .. })
+        ));
+    }
+}
@@ -0,0 +1,147 @@
+use better_config::{env, Format, JsonConfig, YmlConfig};
+
+#[env(YmlConfig(target = "config-anchors.yml"))]
+pub struct AnchorsConfig {
+    #[conf(from = "primary.host")]
+    pub primary_host: String,
+    #[conf(from = "primary.timeout")]
+    pub primary_timeout: u32,
+    #[conf(from = "replica.host")]
+    pub replica_host: String,
+    #[conf(from = "replica.port", default = "6432")]
+    pub replica_port: u16,
+    #[conf(from = "replica.port", default = "6432")]
+    pub replica_port_option: Option<u16>,
+    #[conf(from = "replica.timeout")]
+    pub replica_timeout: Option<u32>,
+    #[conf(from = "replica.missing", default = "1")]
+    pub missing: Option<u32>,
+    #[conf(from = "secret")]
+    pub secret: String,
+    #[conf(from = "ports.8080")]
+    pub http: String,
+    #[conf(from = "ports.true")]
+    pub enabled: String,
+}
+
+#[env(YmlConfig(target = "config.yml,config-null.yml"))]
+pub struct LayeredConfig {
+    #[conf(from = "database.host")]
+    pub host: String,
+    #[conf(from = "database.port")]
+    pub port: Option<u16>,
+}
+
+#[env(JsonConfig)]
+pub struct JsonNullConfig {
+    #[conf(from = "name")]
+    pub name: Option<String>,
+    #[conf(from = "version", default = "1.0")]
+    pub version: Option<f64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+    use std::collections::HashSet;
+    use std::env;
+
+    fn fixture() {
+        env::remove_var("REPLICA_PORT");
+        env::remove_var("DATABASE_PORT");
+        env::remove_var("NAME");
+        env::remove_var("VERSION");
+    }
+
+    #[test]
+    #[serial]
+    fn merge_keys_tags_and_keys() {
+        fixture();
+        let config = AnchorsConfig::builder().build().unwrap();
+        assert_eq!(config.primary_host, "10.0.0.1");
+        assert_eq!(config.primary_timeout, 30);
+        assert_eq!(config.replica_host, "127.0.0.1");
+        assert_eq!(config.replica_timeout, Some(30));
+        assert_eq!(config.secret, "prod/db/password");
+        assert_eq!(config.http, "http");
+        assert_eq!(config.enabled, "enabled");
+    }
+
+    #[test]
+    #[serial]
+    fn null_clears_default() {
+        fixture();
+        let config = AnchorsConfig::builder().build().unwrap();
+        // null overrides the merged base value, plain fields fall back to their default
+        assert_eq!(config.replica_port, 6432);
+        assert_eq!(config.replica_port_option, None);
+        assert_eq!(config.missing, Some(1));
+    }
+
+    #[test]
+    #[serial]
+    fn null_overrides_earlier_targets() {
+        fixture();
+        let config = LayeredConfig::builder().build().unwrap();
+        assert_eq!(config.host, "127.0.0.1");
+        assert_eq!(config.port, None);
+
+        env::set_var("DATABASE_PORT", "3307");
+        let config = LayeredConfig::builder().build().unwrap();
+        assert_eq!(config.port, Some(3307));
+        fixture();
+    }
+
+    #[test]
+    #[serial]
+    fn load_detailed_returns_null_keys() {
+        fixture();
+        let target = Some("config.yml,config-null.yml".to_string());
+        let loaded =
+            <LayeredConfig as YmlConfig>::load_detailed(target.clone(), &HashSet::new()).unwrap();
+        assert_eq!(loaded.values["database.host"], "127.0.0.1");
+        assert!(!loaded.values.contains_key("database.port"));
+        assert_eq!(loaded.nulls, HashSet::from(["database.port".to_string()]));
+
+        // the values alone, nothing is left behind for the next build
+        let params = <LayeredConfig as YmlConfig>::load(target).unwrap();
+        assert!(!params.contains_key("database.port"));
+        let config = JsonNullConfig::builder().source_str("{}").build().unwrap();
+        assert_eq!(config.version, Some(1.0));
+    }
+
+    #[test]
+    #[serial]
+    fn json_null() {
+        fixture();
+        let config = JsonNullConfig::builder()
+            .source_str(r#"{"name": null, "version": null}"#)
+            .build()
+            .unwrap();
+        assert_eq!(config.name, None);
+        assert_eq!(config.version, None);
+
+        let config = JsonNullConfig::builder().source_str("{}").build().unwrap();
+        assert_eq!(config.version, Some(1.0));
+    }
+
+    #[test]
+    #[serial]
+    fn null_is_not_loaded_and_nul_strings_are_kept() {
+        fixture();
+        let params =
+            better_config::parse_str(r#"{"name": null, "version": "\u0000"}"#, Format::Json)
+                .unwrap();
+        assert!(!params.values.contains_key("name"));
+        assert!(params.nulls.contains("name"));
+        assert_eq!(params.values["version"], "\u{0}");
+
+        let config = JsonNullConfig::builder()
+            .source_str(r#"{"name": "\u0000", "version": null}"#)
+            .build()
+            .unwrap();
+        assert_eq!(config.name.as_deref(), Some("\u{0}"));
+        assert_eq!(config.version, None);
+    }
+}